@@ -26,15 +26,15 @@ struct Config {
     pub max_connections: u32,
 
     // Complex type using explicit JSON deserialization
-    #[conf(deserializer = "serde_json::from_str")]
+    #[conf(deserializer = serde_json::from_str)]
     pub tags: Vec<String>,
 
     // HashMap using explicit JSON deserialization
-    #[conf(deserializer = "serde_json::from_str")]
+    #[conf(deserializer = serde_json::from_str)]
     pub environment_vars: HashMap<String, String>,
 
     // Nested struct using explicit JSON deserialization
-    #[conf(deserializer = "serde_json::from_str")]
+    #[conf(deserializer = serde_json::from_str)]
     pub database: DatabaseConfig,
 }
 
@@ -33,11 +33,11 @@ struct Config {
     pub oauth_token: Option<String>, // APP_OAUTH_TOKEN or APP_OAUTH_TOKEN_FILE
 
     // Custom deserializer
-    #[conf(deserializer = "serde_json::from_str")]
+    #[conf(deserializer = serde_json::from_str)]
     pub tags: Vec<String>, // APP_TAGS (JSON)
 
     // Optional with custom deserializer
-    #[conf(deserializer = "serde_json::from_str")]
+    #[conf(deserializer = serde_json::from_str)]
     pub metadata: Option<HashMap<String, String>>, // APP_METADATA (JSON)
 }
 
@@ -14,14 +14,14 @@ struct Config {
     pub port: u16,
 
     // Uses serde_json::from_str (JSON format)
-    #[conf(deserializer = "serde_json::from_str")]
+    #[conf(deserializer = serde_json::from_str)]
     pub json_tags: Vec<String>,
 
     // Uses custom function (comma-separated)
-    #[conf(deserializer = "comma_separated")]
+    #[conf(deserializer = comma_separated)]
     pub comma_tags: Vec<String>,
     // You can also use any other deserializer like toml::from_str
-    // #[conf(deserializer = "toml::from_str")]
+    // #[conf(deserializer = toml::from_str)]
     // pub toml_config: MyTomlConfig,
 }
 
@@ -0,0 +1,67 @@
+//! Helper binary keeping a checked-in `.env.example` in sync with the config struct
+//!
+//! ```bash
+//! cargo run --example env_example                        # print to stdout
+//! cargo run --example env_example -- .env.example        # write the file
+//! cargo run --example env_example -- --check .env.example  # fail if out of date (CI)
+//! ```
+//!
+//! Copy this file into your application (e.g. `src/bin/env_example.rs`) and
+//! replace `Config` with your own configuration struct. To only check the file,
+//! call `Config::check_env_example(".env.example")` from a test instead.
+
+use serviceconf::{ServiceConf, ServiceConfSpec};
+use std::process::ExitCode;
+
+#[derive(Debug, ServiceConf)]
+#[conf(prefix = "MYAPP_")]
+#[allow(dead_code)]
+struct Config {
+    /// Connection string for the primary database
+    #[conf(from_file)]
+    pub database_url: String,
+
+    /// Port the HTTP server listens on
+    #[conf(default = 8080)]
+    pub port: u16,
+
+    /// Log filter, e.g. `info` or `myapp=debug`
+    #[conf(default_str = "info")]
+    pub log_level: String,
+
+    /// HTTP proxy for outgoing requests
+    pub proxy: Option<String>,
+}
+
+fn main() -> ExitCode {
+    let mut check = false;
+    let mut path = None;
+    for arg in std::env::args().skip(1) {
+        match arg.as_str() {
+            "--check" => check = true,
+            _ => path = Some(arg),
+        }
+    }
+
+    let expected = Config::env_example();
+    let Some(path) = path else {
+        if check {
+            eprintln!("--check requires a file path");
+            return ExitCode::FAILURE;
+        }
+        print!("{}", expected);
+        return ExitCode::SUCCESS;
+    };
+
+    if check {
+        if let Err(e) = Config::check_env_example(&path) {
+            eprintln!("{}; regenerate it with:", e);
+            eprintln!("  cargo run --example env_example -- {}", path);
+            return ExitCode::FAILURE;
+        }
+    } else if let Err(e) = std::fs::write(&path, expected) {
+        eprintln!("Failed to write {}: {}", path, e);
+        return ExitCode::FAILURE;
+    }
+    ExitCode::SUCCESS
+}
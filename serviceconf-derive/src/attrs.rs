@@ -1,9 +1,286 @@
 //! Attribute parsing for `#[conf(...)]` annotations.
 //!
-//! This module extracts and validates configuration attributes from struct fields
-//! during macro expansion.
+//! This module extracts and validates configuration attributes from structs and
+//! their fields during macro expansion.
 
-use syn::{Field, Lit};
+use crate::rename::RenameRule;
+use quote::ToTokens;
+use syn::meta::ParseNestedMeta;
+use syn::{DeriveInput, Field, LitStr};
+
+/// Parsed `#[conf(...)]` attributes from the struct itself.
+#[derive(Debug, Default)]
+pub struct StructAttrs {
+    /// Prefix prepended to every environment variable name.
+    pub prefix: String,
+
+    /// Naming convention used to derive environment variable names from field names.
+    ///
+    /// Does not apply to explicit `name`, `alias` or `deprecated_name` values.
+    pub rename_all: RenameRule,
+
+    /// Functions checking the fully built struct, specified with
+    /// `#[conf(validate = path)]`; may be repeated.
+    pub validate: Vec<syn::Path>,
+
+    /// Stop loading at the first error instead of collecting every error,
+    /// specified with `#[conf(fail_fast)]`.
+    pub fail_fast: bool,
+
+    /// Error type returned by `from_env()`, specified with `#[conf(error = MyError)]`.
+    ///
+    /// Must implement `From<ServiceConfError>`; defaults to `ServiceConfError`.
+    pub error: Option<syn::Type>,
+
+    /// Generate a `Debug` impl that masks secret fields, specified with `#[conf(debug)]`.
+    pub debug: bool,
+
+    /// Generate a `Display` impl listing each environment variable with its
+    /// (masked) value, specified with `#[conf(display)]`.
+    pub display: bool,
+
+    /// Include a truncated snippet of the offending value in parse errors of
+    /// non-secret fields, specified with `#[conf(show_values)]`.
+    pub show_values: bool,
+
+    /// Generate an associated constant per field holding its environment
+    /// variable name, specified with `#[conf(name_consts)]`.
+    pub name_consts: bool,
+}
+
+impl StructAttrs {
+    /// Extract and parse struct-level `#[conf(...)]` attributes.
+    ///
+    /// Unknown attributes, malformed values and unknown `rename_all` rules are
+    /// reported as spanned errors.
+    pub fn from_input(input: &DeriveInput) -> syn::Result<Self> {
+        let mut attrs = Self::default();
+
+        for attr in &input.attrs {
+            if !attr.path().is_ident("conf") {
+                continue;
+            }
+
+            attr.parse_nested_meta(|meta| {
+                // prefix = "..."
+                if meta.path.is_ident("prefix") {
+                    let prefix: LitStr = meta.value()?.parse()?;
+                    attrs.prefix = prefix.value();
+                    return Ok(());
+                }
+
+                // rename_all = "..."
+                if meta.path.is_ident("rename_all") {
+                    attrs.rename_all = RenameRule::from_lit(&meta.value()?.parse()?)?;
+                    return Ok(());
+                }
+
+                // validate = path or "path" (repeatable)
+                if meta.path.is_ident("validate") {
+                    attrs.validate.push(parse_fn_path(meta.value()?)?);
+                    return Ok(());
+                }
+
+                // fail_fast
+                if meta.path.is_ident("fail_fast") {
+                    attrs.fail_fast = true;
+                    return Ok(());
+                }
+
+                // error = Type or "Type"
+                if meta.path.is_ident("error") {
+                    let value = meta.value()?;
+                    let ty = if value.peek(syn::LitStr) {
+                        let lit: syn::LitStr = value.parse()?;
+                        lit.parse::<syn::Type>().map_err(|e| {
+                            syn::Error::new(lit.span(), format!("invalid error type: {}", e))
+                        })?
+                    } else {
+                        value.parse::<syn::Type>()?
+                    };
+                    attrs.error = Some(ty);
+                    return Ok(());
+                }
+
+                // debug / display
+                if meta.path.is_ident("debug") {
+                    attrs.debug = true;
+                    return Ok(());
+                }
+                if meta.path.is_ident("display") {
+                    attrs.display = true;
+                    return Ok(());
+                }
+
+                // show_values
+                if meta.path.is_ident("show_values") {
+                    attrs.show_values = true;
+                    return Ok(());
+                }
+
+                // name_consts
+                if meta.path.is_ident("name_consts") {
+                    attrs.name_consts = true;
+                    return Ok(());
+                }
+
+                Err(unknown_attribute(&meta, Level::Struct))
+            })?;
+        }
+
+        Ok(attrs)
+    }
+}
+
+/// Attribute names accepted on the struct.
+const STRUCT_ATTRIBUTES: &[&str] = &[
+    "prefix",
+    "rename_all",
+    "validate",
+    "fail_fast",
+    "error",
+    "debug",
+    "display",
+    "show_values",
+    "name_consts",
+];
+
+/// Attribute names accepted on fields.
+const FIELD_ATTRIBUTES: &[&str] = &[
+    "name",
+    "alias",
+    "deprecated_name",
+    "note",
+    "default",
+    "default_str",
+    "default_fn",
+    "default_from",
+    "from_file",
+    "deserializer",
+    "with",
+    "range",
+    "min_len",
+    "max_len",
+    "non_empty",
+    "one_of",
+    "regex",
+    "validate",
+    "requires",
+    "conflicts_with",
+    "required_if",
+    "skip",
+    "secret",
+    "group",
+];
+
+/// Where a `#[conf(...)]` attribute appears.
+#[derive(Clone, Copy)]
+enum Level {
+    Struct,
+    Field,
+}
+
+impl Level {
+    fn name(self) -> &'static str {
+        match self {
+            Level::Struct => "struct",
+            Level::Field => "field",
+        }
+    }
+
+    fn attributes(self) -> &'static [&'static str] {
+        match self {
+            Level::Struct => STRUCT_ATTRIBUTES,
+            Level::Field => FIELD_ATTRIBUTES,
+        }
+    }
+
+    fn other(self) -> Level {
+        match self {
+            Level::Struct => Level::Field,
+            Level::Field => Level::Struct,
+        }
+    }
+}
+
+/// Error for an unknown attribute name, suggesting the closest known name or
+/// pointing out attributes that belong on the other level.
+fn unknown_attribute(meta: &ParseNestedMeta, level: Level) -> syn::Error {
+    let path = &meta.path;
+    let name = quote::quote!(#path).to_string().replace(' ', "");
+
+    let mut message = format!("unknown {}-level conf attribute `{}`", level.name(), name);
+    if let Some(suggestion) = did_you_mean(&name, level.attributes().iter().copied()) {
+        message.push_str(&format!(", did you mean `{}`?", suggestion));
+    } else if level.other().attributes().contains(&name.as_str()) {
+        message.push_str(&format!(
+            " (`{}` is a {}-level attribute)",
+            name,
+            level.other().name()
+        ));
+    }
+    syn::Error::new_spanned(path, message)
+}
+
+/// Closest candidate to `name` within a small edit distance, for "did you mean" hints.
+pub fn did_you_mean<'a>(
+    name: &str,
+    candidates: impl IntoIterator<Item = &'a str>,
+) -> Option<&'a str> {
+    let threshold = (name.len() / 3).clamp(1, 3);
+    candidates
+        .into_iter()
+        .map(|candidate| (edit_distance(name, candidate), candidate))
+        .filter(|(distance, _)| *distance <= threshold)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// Optimal string alignment distance (Levenshtein with adjacent transpositions).
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut d = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in d[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            d[i][j] = (d[i - 1][j] + 1)
+                .min(d[i][j - 1] + 1)
+                .min(d[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+    d[a.len()][b.len()]
+}
+
+/// Parse a function path written either bare (`path::to::func`) or as a string
+/// literal (`"path::to::func"`).
+fn parse_fn_path(value: syn::parse::ParseStream) -> syn::Result<syn::Path> {
+    if value.peek(syn::LitStr) {
+        let lit: syn::LitStr = value.parse()?;
+        lit.parse::<syn::Path>()
+            .map_err(|e| syn::Error::new(lit.span(), format!("invalid function path: {}", e)))
+    } else {
+        value.parse()
+    }
+}
+
+/// Check that a `regex` pattern parses, so an invalid pattern is reported at
+/// the attribute rather than by every load at runtime.
+fn check_regex(pattern: &syn::LitStr) -> syn::Result<()> {
+    regex_syntax::Parser::new()
+        .parse(&pattern.value())
+        .map(drop)
+        .map_err(|e| syn::Error::new_spanned(pattern, format!("invalid regex pattern: {}", e)))
+}
 
 /// Parsed `#[conf(...)]` attributes from a struct field.
 ///
@@ -16,27 +293,147 @@ pub struct FieldAttrs {
     /// If `None`, the field name is converted to UPPER_SNAKE_CASE.
     pub name: Option<String>,
 
+    /// Fallback environment variable names tried after the primary name.
+    ///
+    /// Specified with `#[conf(alias = "OLD_NAME")]`; may be repeated.
+    pub aliases: Vec<String>,
+
+    /// Retired environment variable names that are still accepted with a warning.
+    ///
+    /// Specified with `#[conf(deprecated_name = "OLD_NAME", note = "...")]`; may be repeated.
+    pub deprecated_names: Vec<DeprecatedName>,
+
     /// Default value strategy:
     /// - `None`: Field is required (no default)
     /// - `Some(None)`: Use `Default::default()`
     /// - `Some(Some(tokens))`: Use explicit token stream as default value
     pub default: Option<Option<proc_macro2::TokenStream>>,
 
+    /// Default value written in environment variable syntax, specified with
+    /// `#[conf(default_str = "60s")]`.
+    ///
+    /// Parsed with the same `FromStr` implementation or custom deserializer as
+    /// values read from the environment.
+    pub default_str: Option<syn::LitStr>,
+
+    /// Function computing the default value, specified with `#[conf(default_fn = path)]`.
+    ///
+    /// Called with no arguments, or with references to the `default_from` fields.
+    pub default_fn: Option<syn::Path>,
+
+    /// Fields the default value is derived from, specified with
+    /// `#[conf(default_from = "other_field")]`; may be repeated.
+    ///
+    /// Without `default_fn`, the single referenced field's value is cloned.
+    pub default_from: Vec<syn::LitStr>,
+
     /// Enable `{VAR}_FILE` pattern for reading secrets from mounted files.
     pub from_file: bool,
 
-    /// Custom deserializer function path (e.g., `"serde_json::from_str"`).
+    /// Custom deserializer function: a path (e.g., `serde_json::from_str`),
+    /// a closure, or any expression callable as `fn(&str) -> Result<T, E>`.
     ///
+    /// The legacy string form (`"serde_json::from_str"`) is parsed into an expression.
     /// When specified, bypasses `FromStr` and uses this function instead.
-    pub deserializer: Option<String>,
+    pub deserializer: Option<syn::Expr>,
+
+    /// Module providing a paired `parse(&str) -> Result<T, E>` and
+    /// `to_env_string(&T) -> String`, specified with `#[conf(with = module)]`.
+    ///
+    /// `parse` is used in place of `FromStr`; `to_env_string` is used when the
+    /// `Display` impl renders the value back into environment variable form,
+    /// and to render computed defaults in generated files.
+    pub with: Option<syn::Path>,
+
+    /// Rules checked against the parsed value, in declaration order.
+    ///
+    /// Specified with `range(...)`, `min_len`, `max_len`, `non_empty`, `one_of`
+    /// and `regex`.
+    pub validations: Vec<Validation>,
+
+    /// Presence constraints relating this field's variable to other fields'.
+    ///
+    /// Specified with `requires`, `conflicts_with` and `required_if(...)`.
+    pub constraints: Vec<Constraint>,
+
+    /// Do not load this field from the environment, specified with `#[conf(skip)]`.
+    ///
+    /// The field is initialized from `default` (or `Default::default()`) instead.
+    pub skip: bool,
+
+    /// Whether the value is masked in generated `Debug` and `Display` output,
+    /// specified with `#[conf(secret)]` or `#[conf(secret = false)]`.
+    ///
+    /// Unset means secret for `from_file` fields; see [`FieldAttrs::is_secret`].
+    pub secret: Option<bool>,
+
+    /// Heading the field is listed under in generated reference tables,
+    /// specified with `#[conf(group = "...")]`.
+    pub group: Option<String>,
+}
+
+/// A cross-field constraint declared on a field, checked on raw variable presence.
+#[derive(Debug)]
+pub enum Constraint {
+    /// `requires = "field"`: if this variable is set, the other must be set too
+    Requires(syn::LitStr),
+    /// `conflicts_with = "field"`: this variable and the other cannot both be set
+    ConflictsWith(syn::LitStr),
+    /// `required_if(field = "value")`: this variable must be set when the other
+    /// variable's raw value equals `value`
+    RequiredIf {
+        field: syn::Ident,
+        value: syn::LitStr,
+    },
+}
+
+/// A declarative validation rule declared on a field.
+#[derive(Debug)]
+pub enum Validation {
+    /// `range(min = ..., max = ...)`: inclusive bounds, either of which may be omitted
+    Range {
+        min: Option<Box<syn::Expr>>,
+        max: Option<Box<syn::Expr>>,
+    },
+    /// `min_len = n`: minimum number of elements (characters for strings)
+    MinLen(usize),
+    /// `max_len = n`: maximum number of elements (characters for strings)
+    MaxLen(usize),
+    /// `non_empty`: at least one element (character for strings)
+    NonEmpty,
+    /// `one_of = [...]`: value must equal one of the listed values
+    OneOf(Vec<syn::Expr>),
+    /// `regex = "..."`: string value must match the pattern (`regex` feature)
+    Regex(syn::LitStr),
+    /// `validate = path`: function called with a reference to the value
+    Custom(syn::Path),
+}
+
+/// A deprecated environment variable name declared on a field.
+#[derive(Debug)]
+pub struct DeprecatedName {
+    /// Deprecated environment variable name (without prefix)
+    pub name: String,
+
+    /// Optional note included in the deprecation warning.
+    pub note: Option<String>,
 }
 
 impl FieldAttrs {
+    /// Whether the value must be masked; `from_file` fields are secret unless
+    /// marked `secret = false`.
+    pub fn is_secret(&self) -> bool {
+        self.secret.unwrap_or(self.from_file)
+    }
+
     /// Extract and parse `#[conf(...)]` attributes from a struct field.
     ///
-    /// Silently ignores unrecognized attributes to allow other macros to process them.
-    pub fn from_field(field: &Field) -> Self {
+    /// Attributes other than `#[conf(...)]` are ignored to allow other macros to
+    /// process them. Malformed `#[conf(...)]` contents are reported as spanned errors.
+    pub fn from_field(field: &Field) -> syn::Result<Self> {
         let mut attrs = Self::default();
+        // Attributes that only make sense on loaded fields, to reject them with `skip`
+        let mut loading_attrs = Vec::new();
 
         for attr in &field.attrs {
             if !attr.path().is_ident("conf") {
@@ -44,14 +441,48 @@ impl FieldAttrs {
             }
 
             // Parse #[conf(...)] contents
-            let _ = attr.parse_nested_meta(|meta| {
+            attr.parse_nested_meta(|meta| {
+                if !meta.path.is_ident("skip") && !meta.path.is_ident("default") {
+                    loading_attrs.push(meta.path.clone());
+                }
+
+                // skip
+                if meta.path.is_ident("skip") {
+                    attrs.skip = true;
+                    return Ok(());
+                }
+
                 // name = "..."
                 if meta.path.is_ident("name") {
-                    let value = meta.value()?;
-                    let name: Lit = value.parse()?;
-                    if let Lit::Str(s) = name {
-                        attrs.name = Some(s.value());
-                    }
+                    let name: LitStr = meta.value()?.parse()?;
+                    attrs.name = Some(name.value());
+                    return Ok(());
+                }
+
+                // alias = "..." (repeatable)
+                if meta.path.is_ident("alias") {
+                    let alias: LitStr = meta.value()?.parse()?;
+                    attrs.aliases.push(alias.value());
+                    return Ok(());
+                }
+
+                // deprecated_name = "..." (repeatable)
+                if meta.path.is_ident("deprecated_name") {
+                    let name: LitStr = meta.value()?.parse()?;
+                    attrs.deprecated_names.push(DeprecatedName {
+                        name: name.value(),
+                        note: None,
+                    });
+                    return Ok(());
+                }
+
+                // note = "..." (applies to the preceding deprecated_name)
+                if meta.path.is_ident("note") {
+                    let note: LitStr = meta.value()?.parse()?;
+                    let Some(deprecated) = attrs.deprecated_names.last_mut() else {
+                        return Err(meta.error("note must follow deprecated_name"));
+                    };
+                    deprecated.note = Some(note.value());
                     return Ok(());
                 }
 
@@ -60,8 +491,8 @@ impl FieldAttrs {
                     if meta.input.peek(syn::Token![=]) {
                         // default = value - explicit value
                         let value = meta.value()?;
-                        let tokens: proc_macro2::TokenStream = value.parse()?;
-                        attrs.default = Some(Some(tokens));
+                        let expr: syn::Expr = value.parse()?;
+                        attrs.default = Some(Some(expr.into_token_stream()));
                     } else {
                         // default - use Default::default()
                         attrs.default = Some(None);
@@ -69,33 +500,261 @@ impl FieldAttrs {
                     return Ok(());
                 }
 
+                // default_str = "..."
+                if meta.path.is_ident("default_str") {
+                    let value = meta.value()?;
+                    attrs.default_str = Some(value.parse()?);
+                    return Ok(());
+                }
+
+                // default_fn = path or "path"
+                if meta.path.is_ident("default_fn") {
+                    attrs.default_fn = Some(parse_fn_path(meta.value()?)?);
+                    return Ok(());
+                }
+
+                // default_from = "field" (repeatable)
+                if meta.path.is_ident("default_from") {
+                    let value = meta.value()?;
+                    attrs.default_from.push(value.parse()?);
+                    return Ok(());
+                }
+
+                // secret or secret = bool
+                if meta.path.is_ident("secret") {
+                    let secret = if meta.input.peek(syn::Token![=]) {
+                        meta.value()?.parse::<syn::LitBool>()?.value
+                    } else {
+                        true
+                    };
+                    attrs.secret = Some(secret);
+                    return Ok(());
+                }
+
+                // group = "..."
+                if meta.path.is_ident("group") {
+                    let group: LitStr = meta.value()?.parse()?;
+                    attrs.group = Some(group.value());
+                    return Ok(());
+                }
+
                 // from_file
                 if meta.path.is_ident("from_file") {
                     attrs.from_file = true;
                     return Ok(());
                 }
 
-                // deserializer = "function::path"
+                // deserializer = function::path, closure, or "function::path"
                 if meta.path.is_ident("deserializer") {
                     let value = meta.value()?;
-                    let func: Lit = value.parse()?;
-                    if let Lit::Str(s) = func {
-                        attrs.deserializer = Some(s.value());
+                    let func = if value.peek(syn::LitStr) {
+                        let lit: syn::LitStr = value.parse()?;
+                        lit.parse::<syn::Expr>().map_err(|e| {
+                            syn::Error::new(
+                                lit.span(),
+                                format!("invalid deserializer function path: {}", e),
+                            )
+                        })?
+                    } else {
+                        value.parse::<syn::Expr>()?
+                    };
+                    attrs.deserializer = Some(func);
+                    return Ok(());
+                }
+
+                // with = module::path or "module::path"
+                if meta.path.is_ident("with") {
+                    let value = meta.value()?;
+                    let module = if value.peek(syn::LitStr) {
+                        let lit: syn::LitStr = value.parse()?;
+                        lit.parse::<syn::Path>().map_err(|e| {
+                            syn::Error::new(lit.span(), format!("invalid module path: {}", e))
+                        })?
+                    } else {
+                        value.parse::<syn::Path>()?
+                    };
+                    attrs.with = Some(module);
+                    return Ok(());
+                }
+
+                // range(min = ..., max = ...)
+                if meta.path.is_ident("range") {
+                    let (mut min, mut max) = (None, None);
+                    meta.parse_nested_meta(|bound| {
+                        if bound.path.is_ident("min") {
+                            min = Some(Box::new(bound.value()?.parse()?));
+                            return Ok(());
+                        }
+                        if bound.path.is_ident("max") {
+                            max = Some(Box::new(bound.value()?.parse()?));
+                            return Ok(());
+                        }
+                        Err(bound.error("expected `min` or `max`"))
+                    })?;
+                    if min.is_none() && max.is_none() {
+                        return Err(meta.error("range requires `min`, `max` or both"));
+                    }
+                    attrs.validations.push(Validation::Range { min, max });
+                    return Ok(());
+                }
+
+                // min_len = n / max_len = n
+                if meta.path.is_ident("min_len") || meta.path.is_ident("max_len") {
+                    let lit: syn::LitInt = meta.value()?.parse()?;
+                    let len = lit.base10_parse::<usize>()?;
+                    attrs.validations.push(if meta.path.is_ident("min_len") {
+                        Validation::MinLen(len)
+                    } else {
+                        Validation::MaxLen(len)
+                    });
+                    return Ok(());
+                }
+
+                // non_empty
+                if meta.path.is_ident("non_empty") {
+                    attrs.validations.push(Validation::NonEmpty);
+                    return Ok(());
+                }
+
+                // one_of = [...]
+                if meta.path.is_ident("one_of") {
+                    let array: syn::ExprArray = meta.value()?.parse()?;
+                    if array.elems.is_empty() {
+                        return Err(syn::Error::new_spanned(
+                            array,
+                            "one_of requires at least one value",
+                        ));
                     }
+                    attrs
+                        .validations
+                        .push(Validation::OneOf(array.elems.into_iter().collect()));
+                    return Ok(());
+                }
+
+                // validate = path or "path" (repeatable)
+                if meta.path.is_ident("validate") {
+                    let func = parse_fn_path(meta.value()?)?;
+                    attrs.validations.push(Validation::Custom(func));
                     return Ok(());
                 }
 
-                Err(meta.error("unsupported conf attribute"))
-            });
+                // requires = "field" / conflicts_with = "field" (repeatable)
+                if meta.path.is_ident("requires") {
+                    attrs
+                        .constraints
+                        .push(Constraint::Requires(meta.value()?.parse()?));
+                    return Ok(());
+                }
+                if meta.path.is_ident("conflicts_with") {
+                    attrs
+                        .constraints
+                        .push(Constraint::ConflictsWith(meta.value()?.parse()?));
+                    return Ok(());
+                }
+
+                // required_if(field = "value")
+                if meta.path.is_ident("required_if") {
+                    let mut condition = None;
+                    meta.parse_nested_meta(|inner| {
+                        let Some(field) = inner.path.get_ident().cloned() else {
+                            return Err(inner.error("expected a field name"));
+                        };
+                        if condition.is_some() {
+                            return Err(inner.error(
+                                "required_if takes a single condition; repeat required_if instead",
+                            ));
+                        }
+                        condition = Some(Constraint::RequiredIf {
+                            field,
+                            value: inner.value()?.parse()?,
+                        });
+                        Ok(())
+                    })?;
+                    let Some(condition) = condition else {
+                        return Err(meta.error(
+                            "required_if requires a condition such as `field = \"value\"`",
+                        ));
+                    };
+                    attrs.constraints.push(condition);
+                    return Ok(());
+                }
+
+                // regex = "..."
+                if meta.path.is_ident("regex") {
+                    let pattern: syn::LitStr = meta.value()?.parse()?;
+                    check_regex(&pattern)?;
+                    attrs.validations.push(Validation::Regex(pattern));
+                    return Ok(());
+                }
+
+                Err(unknown_attribute(&meta, Level::Field))
+            })?;
+        }
+
+        if attrs.skip {
+            if let Some(path) = loading_attrs.first() {
+                let name = quote::quote!(#path).to_string().replace(' ', "");
+                return Err(syn::Error::new_spanned(
+                    path,
+                    format!(
+                        "`{}` cannot be used on a skipped field (only `default` is allowed with `skip`)",
+                        name
+                    ),
+                ));
+            }
+        }
+
+        let default_kinds = [
+            attrs.default.is_some(),
+            attrs.default_str.is_some(),
+            attrs.default_fn.is_some() || !attrs.default_from.is_empty(),
+        ];
+        if default_kinds.iter().filter(|&&kind| kind).count() > 1 {
+            return Err(syn::Error::new_spanned(
+                field,
+                "default, default_str and default_fn/default_from cannot be combined",
+            ));
+        }
+
+        if attrs.default_fn.is_none() && attrs.default_from.len() > 1 {
+            return Err(syn::Error::new_spanned(
+                field,
+                "multiple default_from fields require a default_fn to combine them",
+            ));
+        }
+
+        if attrs.deserializer.is_some() && attrs.with.is_some() {
+            return Err(syn::Error::new_spanned(
+                field,
+                "deserializer and with cannot be combined (with already provides `parse`)",
+            ));
+        }
+
+        let min_len = attrs.validations.iter().find_map(|v| match v {
+            Validation::MinLen(n) => Some(*n),
+            _ => None,
+        });
+        let max_len = attrs.validations.iter().find_map(|v| match v {
+            Validation::MaxLen(n) => Some(*n),
+            _ => None,
+        });
+        if let (Some(min), Some(max)) = (min_len, max_len) {
+            if min > max {
+                return Err(syn::Error::new_spanned(
+                    field,
+                    format!("min_len ({}) is greater than max_len ({})", min, max),
+                ));
+            }
         }
 
-        attrs
+        Ok(attrs)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use quote::quote;
     use syn::parse_quote;
 
     #[test]
@@ -105,7 +764,7 @@ mod tests {
             pub field_name: String
         };
 
-        let attrs = FieldAttrs::from_field(&field);
+        let attrs = FieldAttrs::from_field(&field).unwrap();
         assert_eq!(attrs.name, Some("CUSTOM_NAME".to_string()));
     }
 
@@ -116,7 +775,7 @@ mod tests {
             pub field_name: String
         };
 
-        let attrs = FieldAttrs::from_field(&field);
+        let attrs = FieldAttrs::from_field(&field).unwrap();
         assert!(attrs.default.is_some());
     }
 
@@ -127,10 +786,301 @@ mod tests {
             pub field_name: i32
         };
 
-        let attrs = FieldAttrs::from_field(&field);
+        let attrs = FieldAttrs::from_field(&field).unwrap();
         assert!(attrs.default.is_some());
     }
 
+    #[test]
+    fn test_parse_default_str() {
+        let field: Field = parse_quote! {
+            #[conf(default_str = "60s")]
+            pub timeout: Duration
+        };
+
+        let attrs = FieldAttrs::from_field(&field).unwrap();
+        assert_eq!(attrs.default_str.unwrap().value(), "60s");
+        assert!(attrs.default.is_none());
+    }
+
+    #[test]
+    fn test_parse_default_fn_and_from() {
+        let field: Field = parse_quote! {
+            #[conf(default_fn = next_port, default_from = "port")]
+            pub metrics_port: u16
+        };
+
+        let attrs = FieldAttrs::from_field(&field).unwrap();
+        assert!(attrs.default_fn.unwrap().is_ident("next_port"));
+        assert_eq!(attrs.default_from.len(), 1);
+        assert_eq!(attrs.default_from[0].value(), "port");
+    }
+
+    #[test]
+    fn test_parse_default_kinds_conflict() {
+        let field: Field = parse_quote! {
+            #[conf(default_str = "1", default_fn = one)]
+            pub value: u16
+        };
+
+        assert!(FieldAttrs::from_field(&field).is_err());
+    }
+
+    #[test]
+    fn test_parse_default_followed_by_other_attributes() {
+        let field: Field = parse_quote! {
+            #[conf(default = 8080, range(min = 1024))]
+            pub port: u16
+        };
+
+        let attrs = FieldAttrs::from_field(&field).unwrap();
+        let Some(Some(default)) = attrs.default else {
+            panic!("expected explicit default");
+        };
+        assert_eq!(default.to_string(), "8080");
+        assert_eq!(attrs.validations.len(), 1);
+    }
+
+    #[test]
+    fn test_parse_validations() {
+        let field: Field = parse_quote! {
+            #[conf(range(min = 1024, max = 65535), one_of = [8080, 8443])]
+            #[conf(non_empty, min_len = 1, max_len = 8, regex = "^[a-z]+$")]
+            pub port: u16
+        };
+
+        let attrs = FieldAttrs::from_field(&field).unwrap();
+        assert!(matches!(
+            &attrs.validations[..],
+            [
+                Validation::Range {
+                    min: Some(_),
+                    max: Some(_)
+                },
+                Validation::OneOf(values),
+                Validation::NonEmpty,
+                Validation::MinLen(1),
+                Validation::MaxLen(8),
+                Validation::Regex(_),
+            ] if values.len() == 2
+        ));
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid_regex() {
+        let field: Field = parse_quote! {
+            #[conf(regex = "^[a-z+$")]
+            pub region: String
+        };
+
+        let err = FieldAttrs::from_field(&field).unwrap_err();
+        assert!(err.to_string().starts_with("invalid regex pattern: "));
+        assert!(err.to_string().contains("unclosed character class"));
+
+        // Perl classes are Unicode-aware, as when the pattern is compiled at runtime
+        let field: Field = parse_quote! {
+            #[conf(regex = r"^\w+-\d$")]
+            pub region: String
+        };
+        assert!(FieldAttrs::from_field(&field).is_ok());
+    }
+
+    #[test]
+    fn test_parse_custom_validators() {
+        let field: Field = parse_quote! {
+            #[conf(validate = checks::pool_size, validate = "checks::even")]
+            pub pool_size: u32
+        };
+
+        let attrs = FieldAttrs::from_field(&field).unwrap();
+        let names: Vec<_> = attrs
+            .validations
+            .iter()
+            .map(|v| match v {
+                Validation::Custom(path) => quote!(#path).to_string(),
+                other => panic!("unexpected validation {:?}", other),
+            })
+            .collect();
+        assert_eq!(names, vec!["checks :: pool_size", "checks :: even"]);
+
+        let input: DeriveInput = parse_quote! {
+            #[conf(validate = check_tls, fail_fast, error = crate::AppError)]
+            struct Config {
+                pub tls_cert: Option<String>,
+            }
+        };
+
+        let attrs = StructAttrs::from_input(&input).unwrap();
+        assert_eq!(attrs.validate.len(), 1);
+        assert!(attrs.validate[0].is_ident("check_tls"));
+        assert!(attrs.fail_fast);
+        let error = attrs.error.unwrap();
+        assert_eq!(quote!(#error).to_string(), "crate :: AppError");
+    }
+
+    #[test]
+    fn test_parse_constraints() {
+        let field: Field = parse_quote! {
+            #[conf(requires = "tls_key", conflicts_with = "token")]
+            #[conf(required_if(storage = "s3"))]
+            pub tls_cert: Option<String>
+        };
+
+        let attrs = FieldAttrs::from_field(&field).unwrap();
+        assert!(matches!(
+            &attrs.constraints[..],
+            [
+                Constraint::Requires(requires),
+                Constraint::ConflictsWith(conflicts),
+                Constraint::RequiredIf { field, value },
+            ] if requires.value() == "tls_key"
+                && conflicts.value() == "token"
+                && field == "storage"
+                && value.value() == "s3"
+        ));
+
+        let field: Field = parse_quote! {
+            #[conf(required_if())]
+            pub bucket: Option<String>
+        };
+        assert!(FieldAttrs::from_field(&field).is_err());
+    }
+
+    #[test]
+    fn test_parse_validation_errors() {
+        let field: Field = parse_quote! {
+            #[conf(range())]
+            pub port: u16
+        };
+        assert!(FieldAttrs::from_field(&field).is_err());
+
+        let field: Field = parse_quote! {
+            #[conf(min_len = 5, max_len = 2)]
+            pub name: String
+        };
+        let err = FieldAttrs::from_field(&field).unwrap_err();
+        assert_eq!(err.to_string(), "min_len (5) is greater than max_len (2)");
+    }
+
+    #[test]
+    fn test_unknown_attribute_suggestion() {
+        let field: Field = parse_quote! {
+            #[conf(form_file)]
+            pub api_key: String
+        };
+        let err = FieldAttrs::from_field(&field).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "unknown field-level conf attribute `form_file`, did you mean `from_file`?"
+        );
+
+        let input: DeriveInput = parse_quote! {
+            #[conf(from_file)]
+            struct Config {
+                pub api_key: String,
+            }
+        };
+        let err = StructAttrs::from_input(&input).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "unknown struct-level conf attribute `from_file` (`from_file` is a field-level attribute)"
+        );
+    }
+
+    #[test]
+    fn test_non_string_values_are_rejected() {
+        let field: Field = parse_quote! {
+            #[conf(name = 42)]
+            pub port: u16
+        };
+        assert!(FieldAttrs::from_field(&field).is_err());
+
+        let input: DeriveInput = parse_quote! {
+            #[conf(prefix = APP_)]
+            struct Config {
+                pub port: u16,
+            }
+        };
+        assert!(StructAttrs::from_input(&input).is_err());
+    }
+
+    #[test]
+    fn test_did_you_mean() {
+        let candidates = ["prefix", "rename_all", "validate"];
+        assert_eq!(did_you_mean("prefx", candidates), Some("prefix"));
+        assert_eq!(did_you_mean("renmae_all", candidates), Some("rename_all"));
+        assert_eq!(did_you_mean("database", candidates), None);
+    }
+
+    #[test]
+    fn test_parse_skip() {
+        let field: Field = parse_quote! {
+            #[conf(skip)]
+            pub client: Option<String>
+        };
+        let attrs = FieldAttrs::from_field(&field).unwrap();
+        assert!(attrs.skip);
+        assert!(attrs.default.is_none());
+
+        let field: Field = parse_quote! {
+            #[conf(skip, default = 3)]
+            pub retries: u8
+        };
+        let attrs = FieldAttrs::from_field(&field).unwrap();
+        assert!(attrs.skip);
+        assert!(matches!(attrs.default, Some(Some(_))));
+
+        let field: Field = parse_quote! {
+            #[conf(skip, from_file)]
+            pub client: String
+        };
+        let err = FieldAttrs::from_field(&field).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "`from_file` cannot be used on a skipped field (only `default` is allowed with `skip`)"
+        );
+    }
+
+    #[test]
+    fn test_parse_secret() {
+        let field: Field = parse_quote! {
+            #[conf(secret)]
+            pub password: String
+        };
+        assert!(FieldAttrs::from_field(&field).unwrap().is_secret());
+
+        let field: Field = parse_quote! {
+            #[conf(from_file)]
+            pub api_key: String
+        };
+        assert!(FieldAttrs::from_field(&field).unwrap().is_secret());
+
+        let field: Field = parse_quote! {
+            #[conf(from_file, secret = false)]
+            pub ca_cert: String
+        };
+        assert!(!FieldAttrs::from_field(&field).unwrap().is_secret());
+
+        let field: Field = parse_quote! {
+            pub port: u16
+        };
+        assert!(!FieldAttrs::from_field(&field).unwrap().is_secret());
+    }
+
+    #[test]
+    fn test_parse_group() {
+        let field: Field = parse_quote! {
+            #[conf(group = "Database")]
+            pub database_url: String
+        };
+        let attrs = FieldAttrs::from_field(&field).unwrap();
+        assert_eq!(attrs.group.as_deref(), Some("Database"));
+
+        let field: Field = parse_quote! {
+            pub database_url: String
+        };
+        assert!(FieldAttrs::from_field(&field).unwrap().group.is_none());
+    }
+
     #[test]
     fn test_parse_from_file() {
         let field: Field = parse_quote! {
@@ -138,7 +1088,7 @@ mod tests {
             pub field_name: String
         };
 
-        let attrs = FieldAttrs::from_field(&field);
+        let attrs = FieldAttrs::from_field(&field).unwrap();
         assert!(attrs.from_file);
     }
 
@@ -149,11 +1099,42 @@ mod tests {
             pub database_url: String
         };
 
-        let attrs = FieldAttrs::from_field(&field);
+        let attrs = FieldAttrs::from_field(&field).unwrap();
         assert_eq!(attrs.name, Some("DB_URL".to_string()));
         assert!(attrs.from_file);
     }
 
+    #[test]
+    fn test_parse_alias_repeatable() {
+        let field: Field = parse_quote! {
+            #[conf(alias = "DB_URL", alias = "DB")]
+            #[conf(alias = "POSTGRES_URL")]
+            pub database_url: String
+        };
+
+        let attrs = FieldAttrs::from_field(&field).unwrap();
+        assert_eq!(attrs.aliases, vec!["DB_URL", "DB", "POSTGRES_URL"]);
+    }
+
+    #[test]
+    fn test_parse_deprecated_name_with_note() {
+        let field: Field = parse_quote! {
+            #[conf(deprecated_name = "DB_HOST", note = "removed in 1.0")]
+            #[conf(deprecated_name = "PG_HOST")]
+            pub database_host: String
+        };
+
+        let attrs = FieldAttrs::from_field(&field).unwrap();
+        assert_eq!(attrs.deprecated_names.len(), 2);
+        assert_eq!(attrs.deprecated_names[0].name, "DB_HOST");
+        assert_eq!(
+            attrs.deprecated_names[0].note,
+            Some("removed in 1.0".to_string())
+        );
+        assert_eq!(attrs.deprecated_names[1].name, "PG_HOST");
+        assert_eq!(attrs.deprecated_names[1].note, None);
+    }
+
     #[test]
     fn test_parse_default_no_value() {
         let field: Field = parse_quote! {
@@ -161,10 +1142,24 @@ mod tests {
             pub field_name: String
         };
 
-        let attrs = FieldAttrs::from_field(&field);
+        let attrs = FieldAttrs::from_field(&field).unwrap();
         assert!(matches!(attrs.default, Some(None)));
     }
 
+    #[test]
+    fn test_parse_struct_attrs() {
+        let input: DeriveInput = parse_quote! {
+            #[conf(prefix = "APP_", rename_all = "kebab-case")]
+            struct Config {
+                pub port: u16,
+            }
+        };
+
+        let attrs = StructAttrs::from_input(&input).unwrap();
+        assert_eq!(attrs.prefix, "APP_");
+        assert_eq!(attrs.rename_all, RenameRule::Kebab);
+    }
+
     #[test]
     fn test_parse_deserializer() {
         let field: Field = parse_quote! {
@@ -172,7 +1167,67 @@ mod tests {
             pub field_name: Vec<String>
         };
 
-        let attrs = FieldAttrs::from_field(&field);
-        assert_eq!(attrs.deserializer, Some("serde_json::from_str".to_string()));
+        let attrs = FieldAttrs::from_field(&field).unwrap();
+        let func = attrs.deserializer.unwrap();
+        assert_eq!(quote!(#func).to_string(), "serde_json :: from_str");
+    }
+
+    #[test]
+    fn test_parse_deserializer_path() {
+        let field: Field = parse_quote! {
+            #[conf(deserializer = serde_json::from_str, default)]
+            pub field_name: Vec<String>
+        };
+
+        let attrs = FieldAttrs::from_field(&field).unwrap();
+        let func = attrs.deserializer.unwrap();
+        assert_eq!(quote!(#func).to_string(), "serde_json :: from_str");
+        assert!(matches!(attrs.default, Some(None)));
+    }
+
+    #[test]
+    fn test_parse_deserializer_closure() {
+        let field: Field = parse_quote! {
+            #[conf(deserializer = |s: &str| s.parse::<u64>())]
+            pub field_name: u64
+        };
+
+        let attrs = FieldAttrs::from_field(&field).unwrap();
+        assert!(matches!(attrs.deserializer, Some(syn::Expr::Closure(_))));
+    }
+
+    #[test]
+    fn test_parse_with_module() {
+        let field: Field = parse_quote! {
+            #[conf(with = "crate::formats::duration")]
+            pub timeout: std::time::Duration
+        };
+
+        let attrs = FieldAttrs::from_field(&field).unwrap();
+        let module = attrs.with.unwrap();
+        assert_eq!(quote!(#module).to_string(), "crate :: formats :: duration");
+    }
+
+    #[test]
+    fn test_parse_with_and_deserializer_conflict() {
+        let field: Field = parse_quote! {
+            #[conf(with = duration, deserializer = parse_duration)]
+            pub timeout: std::time::Duration
+        };
+
+        assert!(FieldAttrs::from_field(&field).is_err());
+    }
+
+    #[test]
+    fn test_parse_deserializer_invalid_string() {
+        let field: Field = parse_quote! {
+            #[conf(deserializer = "serde_json::")]
+            pub field_name: Vec<String>
+        };
+
+        let err = FieldAttrs::from_field(&field).unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("invalid deserializer function path"));
     }
 }
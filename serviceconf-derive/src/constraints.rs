@@ -0,0 +1,67 @@
+//! Code generation for cross-field constraints.
+//!
+//! Resolves the fields named by `requires`, `conflicts_with` and `required_if`
+//! and generates presence checks that run before any field is parsed.
+
+use crate::attrs::{did_you_mean, Constraint, FieldAttrs};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::ext::IdentExt;
+use syn::Field;
+
+/// Expressions checking every field's constraints, each evaluating to
+/// `Result<(), ServiceConfError>`.
+///
+/// `env_vars` holds the lookup descriptor expression of each field, in
+/// declaration order. References to unknown fields, or to the declaring field
+/// itself, are reported as errors.
+pub fn checks(
+    fields: &[&Field],
+    attrs: &[FieldAttrs],
+    env_vars: &[TokenStream],
+) -> syn::Result<Vec<TokenStream>> {
+    let names: Vec<String> = fields
+        .iter()
+        .map(|field| field.ident.as_ref().unwrap().unraw().to_string())
+        .collect();
+    let resolve = |i: usize, target: &str, span: &dyn quote::ToTokens, attr: &str| match names
+        .iter()
+        .position(|name| name == target)
+    {
+        Some(j) if j == i => Err(syn::Error::new_spanned(
+            span,
+            format!("{} cannot reference the field it is declared on", attr),
+        )),
+        Some(j) => Ok(&env_vars[j]),
+        None => {
+            let mut message = format!("{} references unknown field `{}`", attr, target);
+            if let Some(suggestion) = did_you_mean(target, names.iter().map(String::as_str)) {
+                message.push_str(&format!(", did you mean `{}`?", suggestion));
+            }
+            Err(syn::Error::new_spanned(span, message))
+        }
+    };
+
+    let mut checks = Vec::new();
+    for (i, attrs) in attrs.iter().enumerate() {
+        let this = &env_vars[i];
+        for constraint in &attrs.constraints {
+            checks.push(match constraint {
+                Constraint::Requires(lit) => {
+                    let other = resolve(i, &lit.value(), lit, "requires")?;
+                    quote! { ::serviceconf::validate::requires(#this, #other) }
+                }
+                Constraint::ConflictsWith(lit) => {
+                    let other = resolve(i, &lit.value(), lit, "conflicts_with")?;
+                    quote! { ::serviceconf::validate::conflicts_with(#this, #other) }
+                }
+                Constraint::RequiredIf { field, value } => {
+                    let other = resolve(i, &field.unraw().to_string(), field, "required_if")?;
+                    quote! { ::serviceconf::validate::required_if(#this, #other, #value) }
+                }
+            });
+        }
+    }
+
+    Ok(checks)
+}
@@ -0,0 +1,413 @@
+//! Compile-time handling of field default values.
+//!
+//! Renders defaults for generated documentation, checks `default_str` values
+//! against the field type where the macro can do so itself, and orders field
+//! initialization so that `default_from` dependencies are loaded first.
+
+use crate::attrs::{did_you_mean, FieldAttrs};
+use quote::ToTokens;
+use syn::ext::IdentExt;
+use syn::{Field, Type};
+
+/// Human-readable description of a field's default, as written in the attribute.
+///
+/// Returns `None` for fields without a default.
+pub fn describe(attrs: &FieldAttrs) -> Option<String> {
+    if let Some(lit) = &attrs.default_str {
+        return Some(lit.value());
+    }
+    if let Some(func) = &attrs.default_fn {
+        let args = attrs
+            .default_from
+            .iter()
+            .map(|lit| format!("&{}", lit.value()))
+            .collect::<Vec<_>>()
+            .join(", ");
+        return Some(format!(
+            "{}({})",
+            render_tokens(&func.to_token_stream()),
+            args
+        ));
+    }
+    if let Some(lit) = attrs.default_from.first() {
+        return Some(format!("{}.clone()", lit.value()));
+    }
+    match &attrs.default {
+        Some(Some(tokens)) => Some(render_tokens(tokens)),
+        Some(None) => Some("Default::default()".to_string()),
+        None => None,
+    }
+}
+
+/// A field's default in environment variable syntax, for generated files that
+/// must contain values the field can actually parse.
+///
+/// This is the `default_str` value verbatim, or a literal `default` (`8080`,
+/// `-1`, `true`) on a field parsed with `FromStr`. Returns `None` for fields
+/// without a default and for defaults computed by Rust expressions.
+pub fn env_syntax(attrs: &FieldAttrs) -> Option<String> {
+    if let Some(lit) = &attrs.default_str {
+        return Some(lit.value());
+    }
+    // Custom parsers may expect a different syntax than the literal's
+    if attrs.deserializer.is_some() || attrs.with.is_some() || attrs.default_fn.is_some() {
+        return None;
+    }
+    let Some(Some(tokens)) = &attrs.default else {
+        return None;
+    };
+    let (negative, lit) = match syn::parse2::<syn::Expr>(tokens.clone()).ok()? {
+        syn::Expr::Lit(expr) => (false, expr.lit),
+        syn::Expr::Unary(syn::ExprUnary {
+            op: syn::UnOp::Neg(_),
+            expr,
+            ..
+        }) => match *expr {
+            syn::Expr::Lit(expr) => (true, expr.lit),
+            _ => return None,
+        },
+        _ => return None,
+    };
+    let sign = if negative { "-" } else { "" };
+    match lit {
+        syn::Lit::Int(lit) => Some(format!("{}{}", sign, lit.base10_digits())),
+        syn::Lit::Float(lit) => Some(format!("{}{}", sign, lit.base10_digits())),
+        syn::Lit::Bool(lit) if !negative => Some(lit.value.to_string()),
+        syn::Lit::Char(lit) if !negative => Some(lit.value().to_string()),
+        syn::Lit::Str(lit) if !negative => Some(lit.value()),
+        _ => None,
+    }
+}
+
+/// Order in which fields must be initialized so that every `default_from`
+/// field is loaded before the fields whose defaults reference it.
+///
+/// Declaration order is preserved where there are no dependencies. References
+/// to unknown fields and dependency cycles are reported as errors.
+pub fn initialization_order(fields: &[&Field], attrs: &[FieldAttrs]) -> syn::Result<Vec<usize>> {
+    #[derive(Clone, Copy, PartialEq)]
+    enum Mark {
+        Unvisited,
+        Visiting,
+        Done,
+    }
+
+    struct Walk<'a> {
+        names: Vec<String>,
+        attrs: &'a [FieldAttrs],
+        marks: Vec<Mark>,
+        path: Vec<usize>,
+        order: Vec<usize>,
+    }
+
+    impl Walk<'_> {
+        fn visit(&mut self, i: usize) -> syn::Result<()> {
+            if self.marks[i] == Mark::Done {
+                return Ok(());
+            }
+            self.marks[i] = Mark::Visiting;
+            self.path.push(i);
+
+            for lit in &self.attrs[i].default_from {
+                let target = lit.value();
+                let Some(j) = self.names.iter().position(|name| *name == target) else {
+                    let mut message = format!("default_from references unknown field `{}`", target);
+                    if let Some(suggestion) =
+                        did_you_mean(&target, self.names.iter().map(String::as_str))
+                    {
+                        message.push_str(&format!(", did you mean `{}`?", suggestion));
+                    }
+                    return Err(syn::Error::new_spanned(lit, message));
+                };
+                if self.marks[j] == Mark::Visiting {
+                    let start = self.path.iter().position(|&k| k == j).unwrap();
+                    let cycle = self.path[start..]
+                        .iter()
+                        .chain(std::iter::once(&j))
+                        .map(|&k| self.names[k].as_str())
+                        .collect::<Vec<_>>()
+                        .join(" -> ");
+                    return Err(syn::Error::new_spanned(
+                        lit,
+                        format!("default_from cycle: {}", cycle),
+                    ));
+                }
+                self.visit(j)?;
+            }
+
+            self.path.pop();
+            self.marks[i] = Mark::Done;
+            self.order.push(i);
+            Ok(())
+        }
+    }
+
+    let mut walk = Walk {
+        names: fields
+            .iter()
+            .map(|field| field.ident.as_ref().unwrap().unraw().to_string())
+            .collect(),
+        attrs,
+        marks: vec![Mark::Unvisited; fields.len()],
+        path: Vec::new(),
+        order: Vec::with_capacity(fields.len()),
+    };
+    for i in 0..fields.len() {
+        walk.visit(i)?;
+    }
+    Ok(walk.order)
+}
+
+/// Render a token stream compactly (`Duration::from_secs(60)` rather than
+/// `Duration :: from_secs (60)`), for documentation purposes.
+pub fn render_tokens(tokens: &proc_macro2::TokenStream) -> String {
+    use proc_macro2::{Delimiter, TokenTree};
+
+    let mut out = String::new();
+    let mut prev_word = false;
+    for tree in tokens.clone() {
+        match tree {
+            TokenTree::Group(group) => {
+                let (open, close) = match group.delimiter() {
+                    Delimiter::Parenthesis => ("(", ")"),
+                    Delimiter::Brace => ("{ ", " }"),
+                    Delimiter::Bracket => ("[", "]"),
+                    Delimiter::None => ("", ""),
+                };
+                out.push_str(open);
+                out.push_str(&render_tokens(&group.stream()));
+                out.push_str(close);
+                prev_word = false;
+            }
+            TokenTree::Ident(_) | TokenTree::Literal(_) => {
+                if prev_word {
+                    out.push(' ');
+                }
+                out.push_str(&tree.to_string());
+                prev_word = true;
+            }
+            TokenTree::Punct(punct) => {
+                out.push(punct.as_char());
+                if punct.as_char() == ',' {
+                    out.push(' ');
+                }
+                prev_word = false;
+            }
+        }
+    }
+    out
+}
+
+/// Check a `default_str` value against the field type at compile time.
+///
+/// Only standard library types whose `FromStr` behavior is known to the macro
+/// can be checked; returns `Ok(false)` when the value could not be verified
+/// (custom types or custom parsers), in which case it is checked when loading.
+pub fn check_default_str(ty: &Type, attrs: &FieldAttrs) -> syn::Result<bool> {
+    let Some(lit) = &attrs.default_str else {
+        return Ok(true);
+    };
+    if attrs.deserializer.is_some() || attrs.with.is_some() {
+        return Ok(false);
+    }
+
+    let Type::Path(type_path) = ty else {
+        return Ok(false);
+    };
+    let Some(ident) = type_path.path.get_ident() else {
+        return Ok(false);
+    };
+
+    let value = lit.value();
+    let result = match ident.to_string().as_str() {
+        "bool" => value.parse::<bool>().map(drop).map_err(|e| e.to_string()),
+        "char" => value.parse::<char>().map(drop).map_err(|e| e.to_string()),
+        "String" => Ok(()),
+        "f32" => value.parse::<f32>().map(drop).map_err(|e| e.to_string()),
+        "f64" => value.parse::<f64>().map(drop).map_err(|e| e.to_string()),
+        "u8" => value.parse::<u8>().map(drop).map_err(|e| e.to_string()),
+        "u16" => value.parse::<u16>().map(drop).map_err(|e| e.to_string()),
+        "u32" => value.parse::<u32>().map(drop).map_err(|e| e.to_string()),
+        "u64" => value.parse::<u64>().map(drop).map_err(|e| e.to_string()),
+        "u128" => value.parse::<u128>().map(drop).map_err(|e| e.to_string()),
+        "i8" => value.parse::<i8>().map(drop).map_err(|e| e.to_string()),
+        "i16" => value.parse::<i16>().map(drop).map_err(|e| e.to_string()),
+        "i32" => value.parse::<i32>().map(drop).map_err(|e| e.to_string()),
+        "i64" => value.parse::<i64>().map(drop).map_err(|e| e.to_string()),
+        "i128" => value.parse::<i128>().map(drop).map_err(|e| e.to_string()),
+        // Target-dependent width: check against the widest case
+        "usize" => value.parse::<u64>().map(drop).map_err(|e| e.to_string()),
+        "isize" => value.parse::<i64>().map(drop).map_err(|e| e.to_string()),
+        _ => return Ok(false),
+    };
+
+    result.map(|()| true).map_err(|e| {
+        syn::Error::new_spanned(
+            lit,
+            format!("invalid default_str for type `{}`: {}", ident, e),
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::{parse_quote, Field};
+
+    #[test]
+    fn test_describe() {
+        let field: Field = parse_quote! {
+            #[conf(default_str = "60s")]
+            pub timeout: Duration
+        };
+        let attrs = FieldAttrs::from_field(&field).unwrap();
+        assert_eq!(describe(&attrs), Some("60s".to_string()));
+
+        let field: Field = parse_quote! {
+            #[conf(default)]
+            pub port: u16
+        };
+        let attrs = FieldAttrs::from_field(&field).unwrap();
+        assert_eq!(describe(&attrs), Some("Default::default()".to_string()));
+
+        let field: Field = parse_quote! {
+            #[conf(default = Duration::from_secs(60))]
+            pub timeout: Duration
+        };
+        let attrs = FieldAttrs::from_field(&field).unwrap();
+        assert_eq!(
+            describe(&attrs),
+            Some("Duration::from_secs(60)".to_string())
+        );
+    }
+
+    #[test]
+    fn test_env_syntax() {
+        let env_syntax = |field: Field| env_syntax(&FieldAttrs::from_field(&field).unwrap());
+
+        assert_eq!(
+            env_syntax(parse_quote! {
+                #[conf(default_str = "60s")]
+                pub timeout: Duration
+            }),
+            Some("60s".to_string())
+        );
+        assert_eq!(
+            env_syntax(parse_quote! {
+                #[conf(default = 8_080u16)]
+                pub port: u16
+            }),
+            Some("8080".to_string())
+        );
+        assert_eq!(
+            env_syntax(parse_quote! {
+                #[conf(default = -1.5)]
+                pub offset: f64
+            }),
+            Some("-1.5".to_string())
+        );
+        assert_eq!(
+            env_syntax(parse_quote! {
+                #[conf(default = true)]
+                pub debug: bool
+            }),
+            Some("true".to_string())
+        );
+        assert_eq!(
+            env_syntax(parse_quote! {
+                #[conf(default = Duration::from_secs(60))]
+                pub timeout: Duration
+            }),
+            None
+        );
+        assert_eq!(
+            env_syntax(parse_quote! {
+                #[conf(default = "localhost".to_string())]
+                pub host: String
+            }),
+            None
+        );
+        assert_eq!(
+            env_syntax(parse_quote! {
+                #[conf(default)]
+                pub port: u16
+            }),
+            None
+        );
+        assert_eq!(
+            env_syntax(parse_quote! {
+                #[conf(default = 30, deserializer = parse_minutes)]
+                pub timeout: u64
+            }),
+            None
+        );
+    }
+
+    #[test]
+    fn test_initialization_order() {
+        let fields: Vec<Field> = vec![
+            parse_quote! {
+                #[conf(default_from = "port", default_fn = next_port)]
+                pub metrics_port: u16
+            },
+            parse_quote! { pub host: String },
+            parse_quote! {
+                #[conf(default = 8080)]
+                pub port: u16
+            },
+        ];
+        let refs: Vec<&Field> = fields.iter().collect();
+        let attrs: Vec<FieldAttrs> = fields
+            .iter()
+            .map(|f| FieldAttrs::from_field(f).unwrap())
+            .collect();
+
+        assert_eq!(initialization_order(&refs, &attrs).unwrap(), vec![2, 0, 1]);
+    }
+
+    #[test]
+    fn test_initialization_order_rejects_cycles() {
+        let fields: Vec<Field> = vec![
+            parse_quote! {
+                #[conf(default_from = "b")]
+                pub a: u16
+            },
+            parse_quote! {
+                #[conf(default_from = "a")]
+                pub b: u16
+            },
+        ];
+        let refs: Vec<&Field> = fields.iter().collect();
+        let attrs: Vec<FieldAttrs> = fields
+            .iter()
+            .map(|f| FieldAttrs::from_field(f).unwrap())
+            .collect();
+
+        let err = initialization_order(&refs, &attrs).unwrap_err();
+        assert_eq!(err.to_string(), "default_from cycle: a -> b -> a");
+    }
+
+    #[test]
+    fn test_check_default_str() {
+        let field: Field = parse_quote! {
+            #[conf(default_str = "8080")]
+            pub port: u16
+        };
+        let attrs = FieldAttrs::from_field(&field).unwrap();
+        assert!(check_default_str(&field.ty, &attrs).unwrap());
+
+        let field: Field = parse_quote! {
+            #[conf(default_str = "eighty")]
+            pub port: u16
+        };
+        let attrs = FieldAttrs::from_field(&field).unwrap();
+        assert!(check_default_str(&field.ty, &attrs).is_err());
+
+        let field: Field = parse_quote! {
+            #[conf(default_str = "60s", deserializer = parse_duration)]
+            pub timeout: Duration
+        };
+        let attrs = FieldAttrs::from_field(&field).unwrap();
+        assert!(!check_default_str(&field.ty, &attrs).unwrap());
+    }
+}
@@ -1,12 +1,21 @@
 #![doc = include_str!("../README.md")]
 
 use proc_macro::TokenStream;
-use quote::quote;
+use quote::{quote, quote_spanned};
+use syn::ext::IdentExt;
+use syn::spanned::Spanned;
 use syn::{parse_macro_input, Data, DeriveInput, Fields, Type};
 
 mod attrs;
+mod constraints;
+mod defaults;
+mod names;
+mod redact;
+mod rename;
+mod spec;
+mod validation;
 
-use attrs::FieldAttrs;
+use attrs::{FieldAttrs, StructAttrs};
 
 /// Extract the inner type `T` from `Option<T>`, returning the original type if not an Option.
 ///
@@ -25,10 +34,87 @@ fn extract_option_inner_type(ty: &Type) -> &Type {
     ty
 }
 
+/// Expression evaluating to whether a field is secret: `secret` when set,
+/// otherwise `from_file`, otherwise whether its value type is `serviceconf::Secret<T>`.
+///
+/// The type is checked by the compiler through `serviceconf::de::SecretProbe`
+/// rather than by its spelling, so `use serviceconf::Secret;` and type aliases
+/// are recognized and unrelated types called `Secret` are not. The expression
+/// is constant.
+fn secret_expr(field_type: &Type, attrs: &FieldAttrs) -> proc_macro2::TokenStream {
+    match attrs.secret {
+        Some(secret) => quote! { #secret },
+        None if attrs.from_file => quote! { true },
+        None => {
+            let value_type = value_type(field_type);
+            quote! {
+                {
+                    #[allow(unused_imports)]
+                    use ::serviceconf::de::NotSecret as _;
+                    ::serviceconf::de::SecretProbe::<#value_type>::IS_SECRET
+                }
+            }
+        }
+    }
+}
+
+/// The type a field's value is parsed as: `T` for `Option<T>`, the field type otherwise.
+fn value_type(field_type: &Type) -> &Type {
+    if is_option_type(field_type) {
+        extract_option_inner_type(field_type)
+    } else {
+        field_type
+    }
+}
+
+/// Check whether a type is `Option<T>`.
+fn is_option_type(ty: &Type) -> bool {
+    if let Type::Path(type_path) = ty {
+        type_path
+            .path
+            .segments
+            .last()
+            .map(|seg| seg.ident == "Option")
+            .unwrap_or(false)
+    } else {
+        false
+    }
+}
+
+/// Parser used in place of `FromStr`, called as `fn(&str) -> Result<T, E>`: the
+/// `deserializer` expression, or the `with` module's `parse`.
+///
+/// Deserializers of `Secret<T>` fields produce the inner `T`, so their result is
+/// passed through `SecretProbe::wrap`, which wraps it with `Secret::new` for
+/// `Secret<T>` and leaves it unchanged for other types.
+fn deserializer_fn(field_type: &Type, attrs: &FieldAttrs) -> Option<syn::Expr> {
+    let Some(func) = &attrs.deserializer else {
+        let module = attrs.with.as_ref()?;
+        return Some(syn::parse_quote!(#module::parse));
+    };
+    let value_type = value_type(field_type);
+    Some(syn::parse_quote! {
+        |__value: &str| {
+            #[allow(unused_imports)]
+            use ::serviceconf::de::NotSecret as _;
+            ::serviceconf::de::SecretProbe::<#value_type>::wrap((#func)(__value))
+        }
+    })
+}
+
 /// `ServiceConf` derive macro
 ///
 /// Automatically implements the `from_env()` method on structs for loading configuration
-/// from environment variables.
+/// from environment variables, and `from_env_with_provenance()`, which also returns a
+/// `serviceconf::Provenance` recording where each field's value came from.
+///
+/// It also implements `serviceconf::ServiceConfSpec`, whose `env_vars()` returns a
+/// `serviceconf::EnvVarSpec` per loaded field (name, aliases, type, default, `from_file`,
+/// deserializer and doc comment as written). The trait's provided methods render those as
+/// a documented `.env.example` file (`env_example()`), reference tables (`markdown_table()`
+/// / `html_table()`), a JSON Schema for validating a deployment's environment
+/// (`json_schema()`) and deployment manifest snippets (`kubernetes_env()` /
+/// `compose_env()`). Being trait methods, they never clash with the struct's own items.
 ///
 /// # Supported Attributes
 ///
@@ -48,6 +134,153 @@ fn extract_option_inner_type(ty: &Type) -> &Type {
 /// }
 /// ```
 ///
+/// ### `#[conf(rename_all = "...")]`
+/// Choose the naming convention used to derive environment variable names from
+/// field names. Supported rules: `"SCREAMING_SNAKE_CASE"` (the default),
+/// `"snake_case"`, `"lowercase"`, `"UPPERCASE"`, `"PascalCase"`, `"camelCase"`,
+/// `"kebab-case"`, `"SCREAMING-KEBAB-CASE"` and `"dot.case"`.
+///
+/// The prefix is prepended unchanged, and explicit `name`, `alias` and
+/// `deprecated_name` values are used verbatim. `from_file` appends `_FILE` as
+/// written, so with a rule other than `"SCREAMING_SNAKE_CASE"` or `"UPPERCASE"`
+/// a `from_file` field needs an explicit `name`; otherwise it is a compile error.
+///
+/// ```no_run
+/// use serviceconf::ServiceConf;
+///
+/// #[derive(ServiceConf)]
+/// #[conf(prefix = "myapp.", rename_all = "dot.case")]
+/// struct Config {
+///     pub api_key: String,  // Reads from myapp.api.key
+/// }
+/// ```
+///
+/// ### `#[conf(validate = function)]`
+/// Check the fully loaded struct with `fn(&Self) -> Result<(), E>` where
+/// `E: Display`, for invariants spanning several fields. May be repeated.
+/// Errors are reported as `ServiceConfError::InvalidConfig`.
+///
+/// ```no_run
+/// use serviceconf::ServiceConf;
+///
+/// fn check_pool(config: &Config) -> Result<(), String> {
+///     if config.pool_min > config.pool_max {
+///         return Err("POOL_MIN must not exceed POOL_MAX".to_string());
+///     }
+///     Ok(())
+/// }
+///
+/// #[derive(ServiceConf)]
+/// #[conf(validate = check_pool)]
+/// struct Config {
+///     #[conf(default = 1)]
+///     pub pool_min: u32,
+///     #[conf(default = 10)]
+///     pub pool_max: u32,
+/// }
+/// ```
+///
+/// ### `#[conf(fail_fast)]`
+/// By default `from_env()` keeps going after an error and reports every problem
+/// together (`ServiceConfError::Multiple`, one problem per line). With `fail_fast`
+/// it returns the first error instead.
+///
+/// ```no_run
+/// use serviceconf::ServiceConf;
+///
+/// #[derive(ServiceConf)]
+/// #[conf(fail_fast)]
+/// struct Config {
+///     pub database_url: String,
+///     pub api_key: String,  // Not checked if DATABASE_URL is missing
+/// }
+/// ```
+///
+/// ### `#[conf(error = Type)]`
+/// `from_env()` returns `Result<Self, ServiceConfError>` by default. With `error`,
+/// it returns `Result<Self, Type>` instead, converting through
+/// `From<ServiceConfError>`. `#[conf(error = serviceconf::anyhow::Error)]`
+/// (with the `anyhow` feature) returns an `anyhow::Error`.
+///
+/// ```no_run
+/// use serviceconf::{ServiceConf, ServiceConfError};
+///
+/// #[derive(Debug)]
+/// enum AppError {
+///     Config(ServiceConfError),
+/// }
+///
+/// impl From<ServiceConfError> for AppError {
+///     fn from(e: ServiceConfError) -> Self {
+///         AppError::Config(e)
+///     }
+/// }
+///
+/// #[derive(ServiceConf)]
+/// #[conf(error = AppError)]
+/// struct Config {
+///     pub database_url: String,
+/// }
+///
+/// let result: Result<Config, AppError> = Config::from_env();
+/// ```
+///
+/// ### `#[conf(debug)]` / `#[conf(display)]`
+/// Generate a `Debug` impl (use instead of `#[derive(Debug)]`) and/or a `Display`
+/// impl that mask secret fields as `***`. `Display` writes one `NAME=value` line
+/// per variable, rendering values with the field's `with` module or `Debug`, and
+/// `<unset>` for `Option<T>` fields that are `None`. Skipped fields are left out.
+///
+/// ```no_run
+/// use serviceconf::ServiceConf;
+///
+/// #[derive(ServiceConf)]
+/// #[conf(debug, display)]
+/// struct Config {
+///     pub host: String,
+///     #[conf(from_file)]
+///     pub api_key: String,
+/// }
+///
+/// let config = Config::from_env().unwrap();
+/// println!("{:?}", config);  // Config { host: "localhost", api_key: *** }
+/// println!("{}", config);    // HOST="localhost"
+///                            // API_KEY=***
+/// ```
+///
+/// ### `#[conf(show_values)]`
+/// Append a truncated snippet (up to 32 characters) of the offending value to
+/// parse errors, e.g. `... invalid digit found in string (value: "eighty")`.
+/// Secret fields never show their value; without this attribute no snippet is
+/// shown for any field.
+///
+/// ```no_run
+/// use serviceconf::ServiceConf;
+///
+/// #[derive(ServiceConf)]
+/// #[conf(show_values)]
+/// struct Config {
+///     pub port: u16,
+/// }
+/// ```
+///
+/// ### `#[conf(name_consts)]`
+/// Generate a `pub const` per loaded field, named after the field in upper case,
+/// holding its environment variable name (prefix applied), e.g. `Config::DATABASE_URL`.
+/// Opt-in, since the constants share the struct's namespace with its own items.
+///
+/// ```no_run
+/// use serviceconf::ServiceConf;
+///
+/// #[derive(ServiceConf)]
+/// #[conf(prefix = "APP_", name_consts)]
+/// struct Config {
+///     pub database_url: String,
+/// }
+///
+/// assert_eq!(Config::DATABASE_URL, "APP_DATABASE_URL");
+/// ```
+///
 /// ## Field-level Attributes
 ///
 /// ### `#[conf(name = "CUSTOM_NAME")]`
@@ -63,6 +296,50 @@ fn extract_option_inner_type(ty: &Type) -> &Type {
 /// }
 /// ```
 ///
+/// Without `name`, raw identifiers are read without their `r#` prefix
+/// (`r#type` reads from `TYPE`). Every name a field reads (prefix included, with
+/// explicit `name`, `alias` and `deprecated_name` values and `_FILE` variants) is
+/// checked at compile time to be a legal POSIX variable name. Only names derived
+/// from field names may also contain the separator of a non-POSIX `rename_all`
+/// convention (`-` or `.`).
+///
+/// Two fields may not read the same variable: the full set of names each field
+/// reads (prefix, aliases, deprecated names and `_FILE` variants included) is
+/// compared at compile time, so `name = "PORT"` next to a `port` field, or an
+/// `api_key_file` field next to a `from_file` field `api_key`, is an error.
+///
+/// ### `#[conf(alias = "OLD_NAME")]`
+/// Fall back to other environment variable names when the primary name is not set.
+/// May be repeated; names are tried in the order they are declared, after the
+/// primary name. The struct-level prefix applies to aliases as well.
+///
+/// ```no_run
+/// use serviceconf::ServiceConf;
+///
+/// #[derive(ServiceConf)]
+/// struct Config {
+///     #[conf(alias = "DB_URL")]
+///     pub database_url: String,  // Reads from DATABASE_URL, then DB_URL
+/// }
+/// ```
+///
+/// ### `#[conf(deprecated_name = "OLD_NAME", note = "...")]`
+/// Accept a retired environment variable name, tried after the primary name and
+/// any aliases. Reading a value from it emits a warning through the hook set with
+/// `serviceconf::set_deprecation_hook`, or else the `tracing` or `log` facade
+/// when the corresponding feature is enabled (`tracing` wins if both are). `note` is optional and applies to the
+/// preceding `deprecated_name`. May be repeated.
+///
+/// ```no_run
+/// use serviceconf::ServiceConf;
+///
+/// #[derive(ServiceConf)]
+/// struct Config {
+///     #[conf(deprecated_name = "DB_URL", note = "will be removed in v2")]
+///     pub database_url: String,  // Reads from DATABASE_URL, then DB_URL (with a warning)
+/// }
+/// ```
+///
 /// ### `#[conf(default)]`
 /// Use `Default::default()` when the environment variable is not set.
 ///
@@ -89,6 +366,151 @@ fn extract_option_inner_type(ty: &Type) -> &Type {
 /// }
 /// ```
 ///
+/// ### `#[conf(default_str = "value")]`
+/// Use a default written in environment variable syntax. The string is parsed with
+/// the same `FromStr` implementation, `deserializer` or `with` module as values read
+/// from the environment, so defaults and env syntax cannot drift apart. It appears
+/// verbatim in the generated `from_env()` documentation.
+///
+/// Values for standard library types (integers, floats, `bool`, `char`, `String`)
+/// are checked at compile time. For other types the default is parsed every time
+/// `from_env()` runs, so an invalid default fails loading even where the variable
+/// is set.
+///
+/// ```no_run
+/// use serviceconf::ServiceConf;
+///
+/// fn parse_duration(s: &str) -> Result<std::time::Duration, String> {
+///     let secs = s.strip_suffix('s').ok_or("missing 's' suffix")?;
+///     secs.parse().map(std::time::Duration::from_secs).map_err(|e| format!("{}", e))
+/// }
+///
+/// #[derive(ServiceConf)]
+/// struct Config {
+///     #[conf(default_str = "8080")]
+///     pub port: u16,  // Uses 8080 if PORT not set
+///
+///     #[conf(deserializer = parse_duration, default_str = "60s")]
+///     pub timeout: std::time::Duration,  // Uses parse_duration("60s") if TIMEOUT not set
+/// }
+/// ```
+///
+/// ### `#[conf(default_fn = function)]` / `#[conf(default_from = "field")]`
+/// Compute the default at runtime. `default_fn` names a function called only when
+/// the variable is unset; `default_from` (repeatable) passes references to other
+/// fields of the same struct as arguments. With `default_from` alone, the
+/// referenced field's value is cloned. Referenced fields are loaded first
+/// regardless of declaration order; unknown fields and cycles are compile errors.
+///
+/// ```no_run
+/// use serviceconf::ServiceConf;
+///
+/// fn next_port(port: &u16) -> u16 {
+///     port + 1
+/// }
+///
+/// #[derive(ServiceConf)]
+/// struct Config {
+///     #[conf(default_from = "port", default_fn = next_port)]
+///     pub metrics_port: u16,  // Uses PORT + 1 if METRICS_PORT not set
+///
+///     #[conf(default = 8080)]
+///     pub port: u16,
+///
+///     #[conf(default_from = "port")]
+///     pub admin_port: u16,  // Uses the value of `port` if ADMIN_PORT not set
+///
+///     #[conf(default_fn = std::env::temp_dir)]
+///     pub cache_dir: std::path::PathBuf,
+/// }
+/// ```
+///
+/// ### Validation
+/// Check the parsed value (or default) against declarative rules:
+/// `range(min = a, max = b)` (inclusive, either bound optional), `min_len = n`,
+/// `max_len = n`, `non_empty`, `one_of = [...]` and `regex = "..."` (requires the
+/// `regex` feature of `serviceconf`; the pattern is parsed at compile time). Lengths
+/// count characters for strings and elements for collections. `Option<T>` fields are only checked when set.
+///
+/// `validate = function` (repeatable) calls `fn(&T) -> Result<(), E>` with `E: Display`
+/// for checks the built-in rules cannot express; the function name is reported as
+/// the rule. For secret fields, validation errors never include the value: `range`
+/// leaves it out and the message of a `validate` function is replaced.
+///
+/// ```no_run
+/// use serviceconf::ServiceConf;
+///
+/// #[derive(ServiceConf)]
+/// struct Config {
+///     #[conf(default = 8080, range(min = 1024, max = 65535))]
+///     pub port: u16,
+///
+///     #[conf(non_empty)]
+///     pub database_url: String,
+///
+///     #[conf(default_str = "info", one_of = ["debug", "info", "warn", "error"])]
+///     pub log_level: String,
+///
+///     #[conf(default = 4, validate = is_power_of_two)]
+///     pub shards: u32,
+/// }
+///
+/// fn is_power_of_two(value: &u32) -> Result<(), String> {
+///     value
+///         .is_power_of_two()
+///         .then_some(())
+///         .ok_or_else(|| format!("{} is not a power of two", value))
+/// }
+/// ```
+///
+/// ### `#[conf(requires = "field")]` / `#[conf(conflicts_with = "field")]` / `#[conf(required_if(field = "value"))]`
+/// Constrain which variables may be set together, referring to other fields by
+/// name. Constraints are checked on the raw presence of the variables (any alias,
+/// deprecated name or `_FILE` variant counts) before any value is parsed; errors
+/// name both variables involved. All three may be repeated.
+///
+/// ```no_run
+/// use serviceconf::ServiceConf;
+///
+/// #[derive(ServiceConf)]
+/// struct Config {
+///     #[conf(requires = "tls_key")]
+///     pub tls_cert: Option<String>,  // TLS_CERT requires TLS_KEY
+///     pub tls_key: Option<String>,
+///
+///     #[conf(conflicts_with = "token")]
+///     pub password: Option<String>,  // PASSWORD and TOKEN cannot both be set
+///     pub token: Option<String>,
+///
+///     #[conf(default_str = "local")]
+///     pub storage: String,
+///     #[conf(required_if(storage = "s3"))]
+///     pub s3_bucket: Option<String>,  // Required when STORAGE=s3
+/// }
+/// ```
+///
+/// ### `#[conf(skip)]` / `#[conf(skip, default = value)]`
+/// Do not load the field from the environment. The field is initialized with
+/// `Default::default()`, or with `value` when given, and is left out of the
+/// generated documentation, validation and constraints. `default` is the only
+/// attribute allowed alongside `skip`.
+///
+/// ```no_run
+/// use serviceconf::ServiceConf;
+/// use std::sync::Arc;
+///
+/// #[derive(ServiceConf)]
+/// struct Config {
+///     pub database_url: String,
+///
+///     #[conf(skip)]
+///     pub client: Option<Arc<String>>,  // Filled in after loading
+///
+///     #[conf(skip, default = Arc::new(Vec::new()))]
+///     pub listeners: Arc<Vec<String>>,
+/// }
+/// ```
+///
 /// ### `#[conf(from_file)]`
 /// Support loading from file-based secrets (Kubernetes/Docker Secrets).
 /// Reads from both `VAR_NAME` and `VAR_NAME_FILE` environment variables.
@@ -103,10 +525,53 @@ fn extract_option_inner_type(ty: &Type) -> &Type {
 /// }
 /// ```
 ///
-/// ### `#[conf(deserializer = "function")]`
+/// ### `#[conf(secret)]` / `#[conf(secret = false)]`
+/// Mask the value in the `Debug` and `Display` impls generated by
+/// `#[conf(debug)]` and `#[conf(display)]`. `from_file` fields are secret by
+/// default; `secret = false` opts out. `serviceconf::Secret<T>` fields are secret
+/// too, however the type is written (imported, aliased or by full path), and a
+/// `deserializer` on them returns the inner `T`; other types named `Secret` are
+/// not treated specially.
+/// Parse errors of secret fields carry a generic message instead of the parser's,
+/// which may quote the value.
+///
+/// ```no_run
+/// use serviceconf::ServiceConf;
+///
+/// #[derive(ServiceConf)]
+/// #[conf(debug)]
+/// struct Config {
+///     #[conf(secret)]
+///     pub password: String,  // Printed as ***
+///
+///     #[conf(from_file, secret = false)]
+///     pub ca_cert: String,  // Printed as is
+/// }
+/// ```
+///
+/// ### `#[conf(group = "...")]`
+/// List the field under the given heading in `markdown_table()` and
+/// `html_table()`. Fields without a group are listed first.
+///
+/// ```no_run
+/// use serviceconf::ServiceConf;
+///
+/// #[derive(ServiceConf)]
+/// struct Config {
+///     #[conf(group = "Database")]
+///     pub database_url: String,
+///
+///     #[conf(group = "Database", default = 10)]
+///     pub pool_size: u32,
+/// }
+/// ```
+///
+/// ### `#[conf(deserializer = function)]`
 /// Use a custom deserializer function for complex types.
 ///
-/// The function signature must be: `fn(&str) -> Result<T, impl std::fmt::Display>`
+/// The value is a path to a function, a closure, or any expression callable as
+/// `fn(&str) -> Result<T, impl std::fmt::Display>`. The string form
+/// (`deserializer = "function"`) is still accepted for compatibility.
 ///
 /// Can be combined with `default` to provide a fallback value, or used with `Option<T>`
 /// to make the field optional:
@@ -124,19 +589,52 @@ fn extract_option_inner_type(ty: &Type) -> &Type {
 /// #[derive(ServiceConf)]
 /// struct Config {
 ///     // Required field with custom deserializer
-///     #[conf(deserializer = "parse_duration_secs")]
+///     #[conf(deserializer = parse_duration_secs)]
 ///     pub timeout: Duration,
 ///
 ///     // With default value (uses default when env var is not set)
-///     #[conf(deserializer = "parse_duration_secs", default = Duration::from_secs(60))]
+///     #[conf(deserializer = parse_duration_secs, default = Duration::from_secs(60))]
 ///     pub retry_interval: Duration,
 ///
 ///     // With Option<T> (None when env var is not set)
-///     #[conf(deserializer = "parse_duration_secs")]
+///     #[conf(deserializer = parse_duration_secs)]
 ///     pub max_timeout: Option<Duration>,
 /// }
 /// ```
 ///
+/// ### `#[conf(with = module)]`
+/// Use a module providing both directions of a custom format, similar to serde's
+/// `with`. The module must contain:
+///
+/// - `parse(&str) -> Result<T, impl std::fmt::Display>`, used when loading
+/// - `to_env_string(&T) -> String`, used when `#[conf(display)]` renders the
+///   value back to environment variable form, and to show a `default` or
+///   `default_fn` value in generated files (`env_example()`, deployment snippets)
+///
+/// Both functions are checked at compile time. Cannot be combined with `deserializer`.
+///
+/// ```no_run
+/// use serviceconf::ServiceConf;
+///
+/// mod duration_secs {
+///     use std::time::Duration;
+///
+///     pub fn parse(s: &str) -> Result<Duration, std::num::ParseIntError> {
+///         s.parse().map(Duration::from_secs)
+///     }
+///
+///     pub fn to_env_string(value: &Duration) -> String {
+///         value.as_secs().to_string()
+///     }
+/// }
+///
+/// #[derive(ServiceConf)]
+/// struct Config {
+///     #[conf(with = duration_secs)]
+///     pub timeout: std::time::Duration,  // TIMEOUT=30
+/// }
+/// ```
+///
 /// # Examples
 ///
 /// **Basic usage:**
@@ -190,11 +688,11 @@ fn extract_option_inner_type(ty: &Type) -> &Type {
 /// #[derive(ServiceConf)]
 /// struct Config {
 ///     // Custom deserializer with explicit default value
-///     #[conf(deserializer = "parse_duration_secs", default = Duration::from_secs(30))]
+///     #[conf(deserializer = parse_duration_secs, default = Duration::from_secs(30))]
 ///     pub timeout: Duration,
 ///
 ///     // Custom deserializer with Default::default()
-///     #[conf(deserializer = "parse_comma_list", default)]
+///     #[conf(deserializer = parse_comma_list, default)]
 ///     pub allowed_hosts: Vec<String>,
 /// }
 ///
@@ -223,27 +721,11 @@ pub fn derive_serviceconf(input: TokenStream) -> TokenStream {
     // Struct name
     let struct_name = &input.ident;
 
-    // Parse struct-level attributes (prefix)
-    let mut prefix = String::new();
-
-    for attr in &input.attrs {
-        if !attr.path().is_ident("conf") {
-            continue;
-        }
-
-        let _ = attr.parse_nested_meta(|meta| {
-            if meta.path.is_ident("prefix") {
-                let value = meta.value()?;
-                let lit: syn::Lit = value.parse()?;
-                if let syn::Lit::Str(s) = lit {
-                    prefix = s.value();
-                }
-                return Ok(());
-            }
-
-            Err(meta.error("unsupported struct-level conf attribute"))
-        });
-    }
+    // Parse struct-level attributes (prefix, rename_all)
+    let struct_attrs = match StructAttrs::from_input(&input) {
+        Ok(attrs) => attrs,
+        Err(e) => return e.to_compile_error().into(),
+    };
 
     // Extract fields
     let fields = match &input.data {
@@ -265,22 +747,29 @@ pub fn derive_serviceconf(input: TokenStream) -> TokenStream {
         }
     };
 
-    // Validate field attributes before code generation to avoid malformed error tokens
+    // Parse and validate field attributes before code generation to avoid malformed error tokens
+    // Skipped fields are set aside and only initialized when the struct is built
+    let mut loaded_fields = Vec::with_capacity(fields.len());
+    let mut field_attrs = Vec::with_capacity(fields.len());
+    let mut skipped_fields = Vec::new();
     for field in fields.iter() {
         let field_type = &field.ty;
-        let attrs = FieldAttrs::from_field(field);
+        let attrs = match FieldAttrs::from_field(field) {
+            Ok(attrs) => attrs,
+            Err(e) => return e.to_compile_error().into(),
+        };
+
+        if attrs.skip {
+            let ident = &field.ident;
+            skipped_fields.push(match attrs.default {
+                Some(Some(value)) => quote! { #ident: #value },
+                _ => quote! { #ident: ::std::default::Default::default() },
+            });
+            continue;
+        }
 
         // Check if type is Option<T>
-        let is_option = if let syn::Type::Path(type_path) = field_type {
-            type_path
-                .path
-                .segments
-                .last()
-                .map(|seg| seg.ident == "Option")
-                .unwrap_or(false)
-        } else {
-            false
-        };
+        let is_option = is_option_type(field_type);
 
         // Validate invalid attribute combinations
         if is_option && attrs.default.is_some() {
@@ -291,151 +780,486 @@ pub fn derive_serviceconf(input: TokenStream) -> TokenStream {
             .to_compile_error()
             .into();
         }
-    }
 
-    // Generate deserialization code for each field
-    let field_initializers = fields.iter().map(|field| {
-        let field_name = field.ident.as_ref().unwrap();
-        let field_type = &field.ty;
+        if is_option && (attrs.default_fn.is_some() || !attrs.default_from.is_empty()) {
+            return syn::Error::new_spanned(
+                field,
+                "Option<T> fields cannot have default_fn or default_from attributes (they default to None automatically)",
+            )
+            .to_compile_error()
+            .into();
+        }
 
-        // Parse attributes
-        let attrs = FieldAttrs::from_field(field);
+        if is_option && attrs.default_str.is_some() {
+            return syn::Error::new_spanned(
+                field,
+                "Option<T> fields cannot have default_str attribute (they default to None automatically)",
+            )
+            .to_compile_error()
+            .into();
+        }
 
-        // Check if type is Option<T>
-        let is_option = if let syn::Type::Path(type_path) = field_type {
-            type_path.path.segments.last()
-                .map(|seg| seg.ident == "Option")
-                .unwrap_or(false)
-        } else {
-            false
-        };
+        // Validate generated environment variable names
+        if let Err(e) = names::validate(field, &attrs, &struct_attrs) {
+            return e.to_compile_error().into();
+        }
+
+        loaded_fields.push(field);
+        field_attrs.push(attrs);
+    }
+    let fields = loaded_fields;
+
+    // Reject environment variables read by more than one field
+    if let Err(e) = names::check_collisions(&fields, &field_attrs, &struct_attrs) {
+        return e.to_compile_error().into();
+    }
 
-        // Determine environment variable name
-        let base_name = attrs.name.unwrap_or_else(|| {
-            // Convert field name to UPPER_SNAKE_CASE
-            field_name.to_string().to_uppercase()
+    // Load default_from dependencies before the fields that reference them
+    let order = match defaults::initialization_order(&fields, &field_attrs) {
+        Ok(order) => order,
+        Err(e) => return e.to_compile_error().into(),
+    };
+    let binding = |name: &str| quote::format_ident!("__field_{}", name);
+    let field_names: Vec<_> = fields.iter().map(|field| &field.ident).collect();
+    let field_bindings: Vec<_> = fields
+        .iter()
+        .map(|field| binding(&field.ident.as_ref().unwrap().unraw().to_string()))
+        .collect();
+
+    // Verify that `with` modules provide the serializing half of the pair
+    let with_checks = fields
+        .iter()
+        .zip(&field_attrs)
+        .filter_map(|(field, attrs)| {
+            let module = attrs.with.as_ref()?;
+            let value_type = if is_option_type(&field.ty) {
+                extract_option_inner_type(&field.ty)
+            } else {
+                &field.ty
+            };
+            Some(quote! {
+                const _: fn(&#value_type) -> ::std::string::String = #module::to_env_string;
+            })
         });
+    let with_checks = quote! { #(#with_checks)* };
 
-        // Apply prefix
-        let env_var_name = format!("{}{}", prefix, base_name);
+    // List the environment variables read by from_env() in its generated documentation
+    let env_var_docs: Vec<_> = fields
+        .iter()
+        .zip(&field_attrs)
+        .map(|(field, attrs)| {
+            let env_var_name = names::env_var_name(field, attrs, &struct_attrs);
+            let requirement = match defaults::describe(attrs) {
+                Some(default) => format!("default: `{}`", default),
+                None if is_option_type(&field.ty) => "optional".to_string(),
+                None => "required".to_string(),
+            };
+            let doc = format!(" - `{}` ({})", env_var_name, requirement);
+            quote! { #[doc = #doc] }
+        })
+        .collect();
 
-        let load_from_file = attrs.from_file;
-        let deserializer_fn = attrs.deserializer;
+    // Lookup descriptors shared by every deserialization strategy and constraint
+    let env_vars: Vec<_> = fields
+        .iter()
+        .zip(&field_attrs)
+        .map(|(field, attrs)| names::env_var_tokens(field, attrs, &struct_attrs))
+        .collect();
+
+    // Optional Debug/Display impls that mask secret fields
+    let debug_impl = struct_attrs.debug.then(|| {
+        redact::debug_impl(
+            struct_name,
+            &fields,
+            &field_attrs,
+            !skipped_fields.is_empty(),
+        )
+    });
+    let display_impl = struct_attrs.display.then(|| {
+        let env_var_names: Vec<_> = fields
+            .iter()
+            .zip(&field_attrs)
+            .map(|(field, attrs)| names::env_var_name(field, attrs, &struct_attrs))
+            .collect();
+        redact::display_impl(struct_name, &fields, &field_attrs, &env_var_names)
+    });
 
-        // Generate deserialization expression
-        let deserialize_expr = if is_option && deserializer_fn.is_none() {
-            // Option<T> without deserializer
-            let inner_type = extract_option_inner_type(field_type);
+    // Static metadata of every loaded variable, and (opt-in) a constant per variable name
+    let specs: Vec<_> = fields
+        .iter()
+        .zip(&field_attrs)
+        .map(|(field, attrs)| spec::env_var_spec(field, attrs, &struct_attrs))
+        .collect();
+    let name_consts: Vec<_> = fields
+        .iter()
+        .zip(&field_attrs)
+        .filter(|_| struct_attrs.name_consts)
+        .map(|(field, attrs)| spec::name_const(field, attrs, &struct_attrs))
+        .collect();
 
+    // Where each field's value came from, as recorded by the lookups that loaded it
+    let origins: Vec<_> = fields
+        .iter()
+        .zip(&field_attrs)
+        .map(|(field, attrs)| {
+            let label = field.ident.as_ref().unwrap().unraw().to_string();
+            let env_var_name = names::env_var_name(field, attrs, &struct_attrs);
+            let fallback = match defaults::describe(attrs) {
+                Some(expr) => quote! {
+                    ::serviceconf::Source::Default { expr: ::std::string::String::from(#expr) }
+                },
+                None => quote! { ::serviceconf::Source::Unset },
+            };
             quote! {
-                ::serviceconf::de::deserialize_optional::<#inner_type>(
+                ::serviceconf::Origin::new(
+                    #label,
                     #env_var_name,
-                    #load_from_file
-                )?
+                    __recorder.get(#env_var_name).unwrap_or(#fallback),
+                )
             }
-        } else if let Some(func_path) = deserializer_fn {
-            // Use custom deserializer function
-            let func: proc_macro2::TokenStream = func_path.parse().unwrap();
+        })
+        .collect();
+
+    // Cross-field constraints, checked on raw presence before any field is parsed
+    let constraint_checks = match constraints::checks(&fields, &field_attrs, &env_vars) {
+        Ok(checks) => checks,
+        Err(e) => return e.to_compile_error().into(),
+    };
+
+    // Verify `default_str` values: at compile time for known standard library types,
+    // otherwise on every load by running the field's parser (see `de::check_default_str`)
+    let mut default_str_checks = Vec::new();
+    for ((field, attrs), env_var) in fields.iter().zip(&field_attrs).zip(&env_vars) {
+        let Some(default_str) = &attrs.default_str else {
+            continue;
+        };
+        match defaults::check_default_str(&field.ty, attrs) {
+            Ok(true) => continue,
+            Ok(false) => {}
+            Err(e) => return e.to_compile_error().into(),
+        }
+
+        let field_type = &field.ty;
+        let parse = match deserializer_fn(field_type, attrs) {
+            Some(func) => quote! { #func },
+            None => quote! { |__value: &str| __value.parse::<#field_type>() },
+        };
+        default_str_checks.push(quote! {
+            ::serviceconf::de::check_default_str(#env_var, #default_str, #parse)
+        });
+    }
+
+    // Generate deserialization code for each field
+    let field_initializers: Vec<_> = fields.iter().zip(field_attrs).zip(&env_vars).map(|((field, attrs), env_var)| {
+        let field_type = &field.ty;
+
+        // Check if type is Option<T>
+        let is_option = is_option_type(field_type);
 
-            if is_option {
-                // Option<T> with deserializer
+        // Determine environment variable name (prefix and rename_all applied)
+        let env_var_name = names::env_var_name(field, &attrs, &struct_attrs);
+
+        // Validation rules run on the loaded value
+        let field_binding = binding(&field.ident.as_ref().unwrap().unraw().to_string());
+        let value_type = value_type(field_type);
+        let secret = secret_expr(field_type, &attrs);
+        let checks = validation::checks(
+            &attrs,
+            &env_var_name,
+            &field_binding,
+            value_type,
+            is_option,
+            &secret,
+        );
+
+        let deserializer_fn = deserializer_fn(field_type, &attrs);
+
+        // Default computed from a function and/or previously loaded fields
+        // (bound as references while this field loads)
+        let mut dependencies: Vec<_> = attrs.default_from.iter().map(|lit| binding(&lit.value())).collect();
+        let computed_default = match (&attrs.default_fn, dependencies.first()) {
+            (Some(func), _) => Some(quote! { #func(#(#dependencies),*) }),
+            (None, Some(source)) => Some(quote! { ::std::clone::Clone::clone(#source) }),
+            (None, None) => None,
+        };
+        dependencies.sort();
+        dependencies.dedup();
+
+        // Generate deserialization expression, given the tokens computing the
+        // default when the field has `default_fn`/`default_from`
+        let deserialize = |computed_default: Option<proc_macro2::TokenStream>| {
+        if is_option && deserializer_fn.is_none() {
+                // Option<T> without deserializer
                 let inner_type = extract_option_inner_type(field_type);
 
                 quote! {
-                    match ::serviceconf::de::get_env_value(#env_var_name, #load_from_file) {
-                        Ok(__value) => Some(#func(&__value).map_err(|e| ::serviceconf::ServiceConfError::parse_error::<#inner_type>(#env_var_name, e))?),
-                        Err(::serviceconf::ServiceConfError::Missing { .. }) => None,
-                        Err(e) => return Err(e.into()),
+                    ::serviceconf::de::deserialize_optional::<#inner_type>(#env_var)?
+                }
+            } else if let Some(func) = &deserializer_fn {
+                // Use custom deserializer function (parenthesized so closures can be called)
+
+                if is_option {
+                    // Option<T> with deserializer
+                    let inner_type = extract_option_inner_type(field_type);
+
+                    quote! {
+                        match ::serviceconf::de::get_env_value(#env_var) {
+                            Ok(__env) => Some((#func)(__env.value.as_str()).map_err(|e| __env.parse_error::<#inner_type>(e))?),
+                            Err(::serviceconf::ServiceConfError::Missing { .. }) => None,
+                            Err(e) => return Err(e.into()),
+                        }
                     }
+                } else if let Some(default_str) = &attrs.default_str {
+                    // Default written in env var syntax, parsed with the deserializer
+                    quote! {
+                        match ::serviceconf::de::get_env_value(#env_var) {
+                            Ok(__env) => (#func)(__env.value.as_str()).map_err(|e| __env.parse_error::<#field_type>(e))?,
+                            Err(::serviceconf::ServiceConfError::Missing { .. }) => (#func)(#default_str).map_err(|e| ::serviceconf::ServiceConfError::invalid_default::<#field_type>(#env_var_name, #default_str, e))?,
+                            Err(e) => return Err(e.into()),
+                        }
+                    }
+                } else if let Some(default_value) = &computed_default {
+                    // Computed default with deserializer (only evaluated when unset)
+                    quote! {
+                        match ::serviceconf::de::get_env_value(#env_var) {
+                            Ok(__env) => (#func)(__env.value.as_str()).map_err(|e| __env.parse_error::<#field_type>(e))?,
+                            Err(::serviceconf::ServiceConfError::Missing { .. }) => #default_value,
+                            Err(e) => return Err(e.into()),
+                        }
+                    }
+                } else {
+                    // Non-Option with deserializer
+                    match &attrs.default {
+                        Some(Some(default_value)) => {
+                            // Explicit default value with deserializer
+                            quote! {
+                                match ::serviceconf::de::get_env_value(#env_var) {
+                                    Ok(__env) => (#func)(__env.value.as_str()).map_err(|e| __env.parse_error::<#field_type>(e))?,
+                                    Err(::serviceconf::ServiceConfError::Missing { .. }) => #default_value,
+                                    Err(e) => return Err(e.into()),
+                                }
+                            }
+                        }
+                        Some(None) => {
+                            // Use Default::default() with deserializer
+                            quote! {
+                                match ::serviceconf::de::get_env_value(#env_var) {
+                                    Ok(__env) => (#func)(__env.value.as_str()).map_err(|e| __env.parse_error::<#field_type>(e))?,
+                                    Err(::serviceconf::ServiceConfError::Missing { .. }) => Default::default(),
+                                    Err(e) => return Err(e.into()),
+                                }
+                            }
+                        }
+                        None => {
+                            // Required field with deserializer
+                            quote! {
+                                {
+                                    let __env = ::serviceconf::de::get_env_value(#env_var)?;
+                                    (#func)(__env.value.as_str()).map_err(|e| __env.parse_error::<#field_type>(e))?
+                                }
+                            }
+                        }
+                    }
+                }
+            } else if let Some(default_str) = &attrs.default_str {
+                // Default written in env var syntax, parsed with FromStr
+                quote! {
+                    ::serviceconf::de::deserialize_with_default_str::<#field_type>(
+                        #env_var,
+                        #default_str
+                    )?
+                }
+            } else if let Some(default_value) = &computed_default {
+                // Computed default (only evaluated when unset)
+                quote! {
+                    ::serviceconf::de::deserialize_or_else::<#field_type>(
+                        #env_var,
+                        || #default_value
+                    )?
                 }
             } else {
-                // Non-Option with deserializer
-                match attrs.default {
+                // Use FromStr deserialization (default)
+                match &attrs.default {
                     Some(Some(default_value)) => {
-                        // Explicit default value with deserializer
+                        // Explicit default value
                         quote! {
-                            match ::serviceconf::de::get_env_value(#env_var_name, #load_from_file) {
-                                Ok(__value) => #func(&__value).map_err(|e| ::serviceconf::ServiceConfError::parse_error::<#field_type>(#env_var_name, e))?,
-                                Err(::serviceconf::ServiceConfError::Missing { .. }) => #default_value,
-                                Err(e) => return Err(e.into()),
-                            }
+                            ::serviceconf::de::deserialize_with_default::<#field_type>(
+                                #env_var,
+                                #default_value
+                            )?
                         }
                     }
                     Some(None) => {
-                        // Use Default::default() with deserializer
+                        // Use Default::default()
                         quote! {
-                            match ::serviceconf::de::get_env_value(#env_var_name, #load_from_file) {
-                                Ok(__value) => #func(&__value).map_err(|e| ::serviceconf::ServiceConfError::parse_error::<#field_type>(#env_var_name, e))?,
-                                Err(::serviceconf::ServiceConfError::Missing { .. }) => Default::default(),
-                                Err(e) => return Err(e.into()),
-                            }
+                            ::serviceconf::de::deserialize_with_default::<#field_type>(
+                                #env_var,
+                                Default::default()
+                            )?
                         }
                     }
                     None => {
-                        // Required field with deserializer
+                        // Required field
                         quote! {
-                            {
-                                let __value = ::serviceconf::de::get_env_value(#env_var_name, #load_from_file)?;
-                                #func(&__value).map_err(|e| ::serviceconf::ServiceConfError::parse_error::<#field_type>(#env_var_name, e))?
-                            }
+                            ::serviceconf::de::deserialize_required::<#field_type>(#env_var)?
                         }
                     }
                 }
             }
-        } else {
-            // Use FromStr deserialization (default)
-            match attrs.default {
-                Some(Some(default_value)) => {
-                    // Explicit default value
-                    quote! {
-                        ::serviceconf::de::deserialize_with_default::<#field_type>(
-                            #env_var_name,
-                            #load_from_file,
-                            #default_value
-                        )?
-                    }
-                }
-                Some(None) => {
-                    // Use Default::default()
-                    quote! {
-                        ::serviceconf::de::deserialize_with_default::<#field_type>(
-                            #env_var_name,
-                            #load_from_file,
-                            Default::default()
-                        )?
-                    }
-                }
-                None => {
-                    // Required field
-                    quote! {
-                        ::serviceconf::de::deserialize_required::<#field_type>(
-                            #env_var_name,
-                            #load_from_file
-                        )?
-                    }
-                }
+        };
+
+        // Load and check the field, evaluating to a `Result`
+        let load_with = |deserialize_expr: proc_macro2::TokenStream| {
+            quote! {
+                (|| -> ::std::result::Result<#field_type, ::serviceconf::ServiceConfError> {
+                    let #field_binding: #field_type = #deserialize_expr;
+                    #checks
+                    ::std::result::Result::Ok(#field_binding)
+                })()
             }
         };
+        let load = load_with(deserialize(computed_default.clone()));
 
+        if struct_attrs.fail_fast {
+            quote! {
+                let #field_binding: #field_type = {
+                    #(let #dependencies = &#dependencies;)*
+                    #load
+                }?;
+            }
+        } else if dependencies.is_empty() {
+            quote! {
+                let #field_binding: ::std::option::Option<#field_type> = __errors.take(#load);
+            }
+        } else {
+            // When a dependency failed (its error is already recorded) the default
+            // cannot be computed, but a set variable is still loaded so that its
+            // own errors are reported in the same run; the default is never
+            // needed then, since a set variable is always found
+            let load_set = load_with(deserialize(Some(quote! {
+                ::std::unreachable!("default of a set variable")
+            })));
+            quote! {
+                let #field_binding: ::std::option::Option<#field_type> =
+                    match (#(&#dependencies,)*) {
+                        (#(::std::option::Option::Some(#dependencies),)*) => __errors.take(#load),
+                        _ if ::serviceconf::de::is_set(#env_var) => __errors.take(#load_set),
+                        _ => ::std::option::Option::None,
+                    };
+            }
+        }
+    }).collect();
+    let field_initializers = order.iter().map(|&i| &field_initializers[i]);
+
+    // Struct-level validators run once every field has been loaded
+    let struct_checks =
+        validation::struct_checks(&struct_attrs.validate, &quote::format_ident!("__config"));
+
+    // Either stop at the first error or collect every error before failing
+    let load_body = if struct_attrs.fail_fast {
         quote! {
-            #field_name: #deserialize_expr
+            #(#constraint_checks?;)*
+            #(#default_str_checks?;)*
+            #(#field_initializers)*
+
+            let __config = Self {
+                #(#field_names: #field_bindings,)*
+                #(#skipped_fields,)*
+            };
+            #(#struct_checks?;)*
         }
-    });
+    } else {
+        quote! {
+            let mut __errors = ::serviceconf::de::ErrorCollector::new();
+            #(__errors.check(#constraint_checks);)*
+            #(__errors.check(#default_str_checks);)*
+            #(#field_initializers)*
+            __errors.finish()?;
+
+            let __config = Self {
+                #(#field_names: #field_bindings.unwrap(),)*
+                #(#skipped_fields,)*
+            };
+            #(__errors.check(#struct_checks);)*
+            __errors.finish()?;
+        }
+    };
+
+    // Error type returned by from_env(); custom types convert through `From`
+    let (error_type, convert_error) = match &struct_attrs.error {
+        Some(ty) => (
+            quote! { #ty },
+            quote_spanned! {ty.span()=>
+                .map_err(<#ty as ::std::convert::From<::serviceconf::ServiceConfError>>::from)
+            },
+        ),
+        None => (quote! { ::serviceconf::ServiceConfError }, quote! {}),
+    };
 
     // Generate from_env() method
     let expanded = quote! {
+        #with_checks
+        #debug_impl
+        #display_impl
+
+        impl ::serviceconf::ServiceConfSpec for #struct_name {
+            fn env_vars() -> &'static [::serviceconf::EnvVarSpec] {
+                const SPECS: &[::serviceconf::EnvVarSpec] = &[#(#specs),*];
+                SPECS
+            }
+        }
+
         impl #struct_name {
+            #(#name_consts)*
+
             /// Load configuration from environment variables
             ///
+            /// # Environment Variables
+            ///
+            #(#env_var_docs)*
+            ///
             /// # Errors
             ///
             /// - Required environment variables are not set
             /// - Environment variable values cannot be parsed into target types
             /// - File-based configuration fails to read files
-            pub fn from_env() -> ::serviceconf::anyhow::Result<Self> {
-                Ok(Self {
-                    #(#field_initializers),*
-                })
+            /// - Values fail validation rules or `validate` functions
+            /// - `requires`, `conflicts_with` or `required_if` constraints are violated
+            ///
+            /// Unless the struct is marked `#[conf(fail_fast)]`, every problem is
+            /// collected and reported together as `ServiceConfError::Multiple`.
+            pub fn from_env() -> ::std::result::Result<Self, #error_type> {
+                Self::__serviceconf_load(::std::option::Option::None)
+            }
+
+            /// Load configuration like [`Self::from_env`], also reporting where each
+            /// field's value came from
+            ///
+            /// Each field's origin is the environment variable (or `_FILE` variant,
+            /// with its path) its value was read from, or its default when unset.
+            ///
+            /// # Errors
+            ///
+            /// Fails exactly when [`Self::from_env`] does.
+            pub fn from_env_with_provenance(
+            ) -> ::std::result::Result<(Self, ::serviceconf::Provenance), #error_type> {
+                let __recorder = ::serviceconf::de::SourceRecorder::new();
+                let __config = Self::__serviceconf_load(::std::option::Option::Some(&__recorder))?;
+                let __provenance = ::serviceconf::Provenance::new(::std::vec![#(#origins),*]);
+                ::std::result::Result::Ok((__config, __provenance))
+            }
+
+            /// Shared body of `from_env` and `from_env_with_provenance`, recording
+            /// the source of each value in `__sources` when given
+            fn __serviceconf_load(
+                __sources: ::std::option::Option<&::serviceconf::de::SourceRecorder>,
+            ) -> ::std::result::Result<Self, #error_type> {
+                (|| -> ::std::result::Result<Self, ::serviceconf::ServiceConfError> {
+                    #load_body
+
+                    Ok(__config)
+                })()
+                #convert_error
             }
         }
     };
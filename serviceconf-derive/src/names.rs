@@ -0,0 +1,490 @@
+//! Environment variable name derivation and validation.
+//!
+//! Computes the names a field is read from (prefix, `rename_all`, `name`,
+//! `alias` and `deprecated_name` applied) and checks them at compile time,
+//! both individually and for collisions between fields.
+
+use crate::attrs::{FieldAttrs, StructAttrs};
+use proc_macro2::TokenStream;
+use quote::quote;
+use std::collections::HashMap;
+use syn::ext::IdentExt;
+use syn::Field;
+
+/// Primary environment variable name of a field, with the prefix applied.
+///
+/// Uses the explicit `name` override if present, otherwise converts the field
+/// name (without any `r#` raw identifier prefix) with the `rename_all` rule.
+pub fn env_var_name(field: &Field, attrs: &FieldAttrs, struct_attrs: &StructAttrs) -> String {
+    let base_name = attrs.name.clone().unwrap_or_else(|| {
+        let field_name = field.ident.as_ref().unwrap().unraw().to_string();
+        struct_attrs.rename_all.apply(&field_name)
+    });
+    format!("{}{}", struct_attrs.prefix, base_name)
+}
+
+/// Expression building the `serviceconf::de::EnvVar` lookup descriptor of a field
+/// (primary name, aliases, deprecated names, `_FILE` support and how parse
+/// errors treat the value). Sources are recorded in the `__sources` recorder in
+/// scope of the generated loading code.
+pub fn env_var_tokens(
+    field: &Field,
+    attrs: &FieldAttrs,
+    struct_attrs: &StructAttrs,
+) -> TokenStream {
+    let prefix = &struct_attrs.prefix;
+    let name = env_var_name(field, attrs, struct_attrs);
+    let aliases = attrs
+        .aliases
+        .iter()
+        .map(|alias| format!("{}{}", prefix, alias));
+    let deprecated = attrs.deprecated_names.iter().map(|deprecated| {
+        let name = format!("{}{}", prefix, deprecated.name);
+        let note = match &deprecated.note {
+            Some(note) => quote! { Some(#note) },
+            None => quote! { None },
+        };
+        quote! { ::serviceconf::de::Deprecated::new(#name, #note) }
+    });
+    let from_file = attrs.from_file;
+    let secret = crate::secret_expr(&field.ty, attrs);
+    let show_value = struct_attrs.show_values;
+
+    quote! {
+        &::serviceconf::de::EnvVar::new(#name)
+            .aliases(&[#(#aliases),*])
+            .deprecated(&[#(#deprecated),*])
+            .from_file(#from_file)
+            .secret(#secret)
+            .show_value(#show_value)
+            .record(__sources)
+    }
+}
+
+/// Check that every name a field is read from can be set as an environment variable.
+///
+/// Every name (prefix included) must be a legal POSIX variable name: non-empty,
+/// made of letters, digits and underscores, and not starting with a digit. This
+/// covers explicit `name`, `alias` and `deprecated_name` values and the `_FILE`
+/// variants of `from_file` fields. Only the primary name derived from the field
+/// may also contain the separator of a non-POSIX `rename_all` rule (`-` or `.`).
+pub fn validate(field: &Field, attrs: &FieldAttrs, struct_attrs: &StructAttrs) -> syn::Result<()> {
+    let prefixed = |name: &str| format!("{}{}", struct_attrs.prefix, name);
+    let primary = (
+        env_var_name(field, attrs, struct_attrs),
+        attrs.name.is_none(),
+    );
+    let explicit = attrs
+        .aliases
+        .iter()
+        .map(|alias| prefixed(alias))
+        .chain(attrs.deprecated_names.iter().map(|d| prefixed(&d.name)))
+        .map(|name| (name, false));
+    let mut names = Vec::new();
+    for (name, generated) in std::iter::once(primary).chain(explicit) {
+        let file_name = attrs.from_file.then(|| format!("{}_FILE", name));
+        names.push((name, generated));
+        names.extend(file_name.map(|name| (name, generated)));
+    }
+
+    let mut errors: Option<syn::Error> = None;
+    for (name, generated) in names {
+        let separator = struct_attrs.rename_all.separator().filter(|_| generated);
+        let problem = if name.is_empty() {
+            Some("it is empty")
+        } else if name.contains('=') {
+            Some("it must not contain `=`")
+        } else if name.contains('\0') {
+            Some("it must not contain a NUL character")
+        } else if !is_posix_name(&name, separator) {
+            Some(match separator {
+                Some('-') => {
+                    "expected letters, digits, underscores and `-`, not starting with a digit"
+                }
+                Some('.') => {
+                    "expected letters, digits, underscores and `.`, not starting with a digit"
+                }
+                _ => "expected letters, digits and underscores, not starting with a digit",
+            })
+        } else {
+            None
+        };
+        let Some(problem) = problem else {
+            continue;
+        };
+        let message = format!(
+            "`{}` is not a valid environment variable name ({})",
+            name, problem
+        );
+        // Point at the field name when the primary name was derived from it
+        let error = match &field.ident {
+            Some(ident) if generated => syn::Error::new_spanned(ident, message),
+            _ => syn::Error::new_spanned(field, message),
+        };
+        match &mut errors {
+            Some(errors) => errors.combine(error),
+            None => errors = Some(error),
+        }
+    }
+
+    // The `_FILE` suffix is appended as written, so a derived name must be in the
+    // same case for the pair to follow one convention (`api-key_FILE` would not)
+    let rule = struct_attrs.rename_all;
+    if attrs.from_file && attrs.name.is_none() && !rule.matches_file_suffix() {
+        let message = format!(
+            "`from_file` would read `{}_FILE`, which does not follow `rename_all = \"{}\"`; \
+             set the variable's `name` explicitly",
+            env_var_name(field, attrs, struct_attrs),
+            rule.as_str()
+        );
+        let error = syn::Error::new_spanned(field.ident.as_ref().unwrap(), message);
+        match &mut errors {
+            Some(errors) => errors.combine(error),
+            None => errors = Some(error),
+        }
+    }
+
+    errors.map_or(Ok(()), Err)
+}
+
+/// Every environment variable a field reads, in lookup order.
+///
+/// Each entry pairs the variable with the name it is the `_FILE` variant of, if any.
+fn read_names(
+    field: &Field,
+    attrs: &FieldAttrs,
+    struct_attrs: &StructAttrs,
+) -> Vec<(String, Option<String>)> {
+    let prefixed = |name: &str| format!("{}{}", struct_attrs.prefix, name);
+    let names = std::iter::once(env_var_name(field, attrs, struct_attrs))
+        .chain(attrs.aliases.iter().map(|alias| prefixed(alias)))
+        .chain(attrs.deprecated_names.iter().map(|d| prefixed(&d.name)));
+
+    let mut read = Vec::new();
+    for name in names {
+        if attrs.from_file {
+            read.push((name.clone(), None));
+            read.push((format!("{}_FILE", name), Some(name)));
+        } else {
+            read.push((name, None));
+        }
+    }
+    read
+}
+
+/// Check that no environment variable is read twice, either by two fields or
+/// by the same field under two of its names.
+///
+/// `_FILE` variants of `from_file` fields take part, so a field named
+/// `api_key_file` collides with a `from_file` field named `api_key`.
+pub fn check_collisions(
+    fields: &[&Field],
+    attrs: &[FieldAttrs],
+    struct_attrs: &StructAttrs,
+) -> syn::Result<()> {
+    let describe = |field: &Field, file_of: &Option<String>| {
+        let ident = field.ident.as_ref().unwrap().unraw();
+        match file_of {
+            Some(base) => format!("`{}` (as the `_FILE` variant of `{}`)", ident, base),
+            None => format!("`{}`", ident),
+        }
+    };
+
+    let mut seen: HashMap<String, (usize, Option<String>)> = HashMap::new();
+    let mut errors: Option<syn::Error> = None;
+    for (i, (field, attrs)) in fields.iter().zip(attrs).enumerate() {
+        for (name, file_of) in read_names(field, attrs, struct_attrs) {
+            let Some((j, first_file_of)) = seen.get(&name) else {
+                seen.insert(name, (i, file_of));
+                continue;
+            };
+            let message = if *j == i {
+                format!(
+                    "environment variable `{}` is listed more than once for field {}",
+                    name,
+                    describe(field, &None)
+                )
+            } else {
+                format!(
+                    "environment variable `{}` is read by both {} and {}",
+                    name,
+                    describe(fields[*j], first_file_of),
+                    describe(field, &file_of)
+                )
+            };
+            let error = syn::Error::new_spanned(field, message);
+            match &mut errors {
+                Some(errors) => errors.combine(error),
+                None => errors = Some(error),
+            }
+        }
+    }
+
+    errors.map_or(Ok(()), Err)
+}
+
+/// Whether `name` matches `[A-Za-z_][A-Za-z0-9_]*`, with `separator` also
+/// allowed after the first character.
+fn is_posix_name(name: &str, separator: Option<char>) -> bool {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(first) if first.is_ascii_alphabetic() || first == '_' => {
+            chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || Some(c) == separator)
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rename::RenameRule;
+    use syn::parse_quote;
+
+    #[test]
+    fn test_raw_identifier_is_unraw() {
+        let field: Field = parse_quote! {
+            pub r#type: String
+        };
+
+        let attrs = FieldAttrs::from_field(&field).unwrap();
+        let name = env_var_name(&field, &attrs, &StructAttrs::default());
+        assert_eq!(name, "TYPE");
+    }
+
+    #[test]
+    fn test_is_posix_name() {
+        assert!(is_posix_name("DATABASE_URL", None));
+        assert!(is_posix_name("_PRIVATE", None));
+        assert!(is_posix_name("app2_port", None));
+        assert!(!is_posix_name("", None));
+        assert!(!is_posix_name("2FA_SECRET", None));
+        assert!(!is_posix_name("MY-APP_PORT", None));
+        assert!(!is_posix_name("R#TYPE", None));
+        assert!(is_posix_name("my-app_port", Some('-')));
+        assert!(!is_posix_name("-port", Some('-')));
+        assert!(!is_posix_name("my.app", Some('-')));
+    }
+
+    #[test]
+    fn test_check_collisions() {
+        let check = |fields: &[Field], struct_attrs: &StructAttrs| {
+            let attrs: Vec<_> = fields
+                .iter()
+                .map(|field| FieldAttrs::from_field(field).unwrap())
+                .collect();
+            let fields: Vec<_> = fields.iter().collect();
+            check_collisions(&fields, &attrs, struct_attrs)
+        };
+
+        let distinct: [Field; 2] = [parse_quote!(pub port: u16), parse_quote!(pub host: String)];
+        assert!(check(&distinct, &StructAttrs::default()).is_ok());
+
+        let renamed: [Field; 2] = [
+            parse_quote!(pub port: u16),
+            parse_quote!(#[conf(name = "PORT")] pub http_port: u16),
+        ];
+        let err = check(&renamed, &StructAttrs::default()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "environment variable `PORT` is read by both `port` and `http_port`"
+        );
+
+        let file_variant: [Field; 2] = [
+            parse_quote!(#[conf(from_file)] pub api_key: String),
+            parse_quote!(pub api_key_file: String),
+        ];
+        let struct_attrs = StructAttrs {
+            prefix: "APP_".to_string(),
+            ..Default::default()
+        };
+        let err = check(&file_variant, &struct_attrs).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "environment variable `APP_API_KEY_FILE` is read by both \
+             `api_key` (as the `_FILE` variant of `APP_API_KEY`) and `api_key_file`"
+        );
+
+        let aliased: [Field; 1] = [parse_quote!(#[conf(alias = "PORT")] pub port: u16)];
+        assert!(check(&aliased, &StructAttrs::default()).is_err());
+    }
+
+    fn validate_field(field: Field, struct_attrs: &StructAttrs) -> syn::Result<()> {
+        let attrs = FieldAttrs::from_field(&field).unwrap();
+        validate(&field, &attrs, struct_attrs)
+    }
+
+    #[test]
+    fn test_validate_rejects_empty_names() {
+        let err = validate_field(
+            parse_quote!(#[conf(name = "")] pub port: u16),
+            &StructAttrs::default(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "`` is not a valid environment variable name (it is empty)"
+        );
+    }
+
+    #[test]
+    fn test_validate_rejects_equals_sign() {
+        let err = validate_field(
+            parse_quote!(#[conf(alias = "PORT=80")] pub port: u16),
+            &StructAttrs::default(),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("`PORT=80`"));
+        assert!(err.to_string().contains("must not contain `=`"));
+
+        let struct_attrs = StructAttrs {
+            prefix: "APP=".to_string(),
+            rename_all: RenameRule::Kebab,
+            ..Default::default()
+        };
+        let err = validate_field(parse_quote!(pub port: u16), &struct_attrs).unwrap_err();
+        assert!(err.to_string().contains("must not contain `=`"));
+    }
+
+    #[test]
+    fn test_validate_rejects_nul() {
+        let err = validate_field(
+            parse_quote!(#[conf(deprecated_name = "OLD\0PORT")] pub port: u16),
+            &StructAttrs::default(),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("must not contain a NUL character"));
+    }
+
+    #[test]
+    fn test_validate_requires_posix_generated_names() {
+        let struct_attrs = StructAttrs {
+            prefix: "MY-APP_".to_string(),
+            ..Default::default()
+        };
+        let err = validate_field(parse_quote!(pub port: u16), &struct_attrs).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "`MY-APP_PORT` is not a valid environment variable name \
+             (expected letters, digits and underscores, not starting with a digit)"
+        );
+
+        let err =
+            validate_field(parse_quote!(pub café: String), &StructAttrs::default()).unwrap_err();
+        assert!(err.to_string().contains("`CAFé`"));
+    }
+
+    #[test]
+    fn test_validate_allows_separator_of_non_posix_rules() {
+        let kebab = StructAttrs {
+            prefix: "my-app-".to_string(),
+            rename_all: RenameRule::Kebab,
+            ..Default::default()
+        };
+        assert!(validate_field(parse_quote!(pub database_url: String), &kebab).is_ok());
+
+        // Still checked: only the rule's own separator is allowed
+        let err = validate_field(parse_quote!(pub café: String), &kebab).unwrap_err();
+        assert!(err.to_string().contains("underscores and `-`"));
+
+        let dot = StructAttrs {
+            prefix: "my-app.".to_string(),
+            rename_all: RenameRule::Dot,
+            ..Default::default()
+        };
+        let err = validate_field(parse_quote!(pub port: u16), &dot).unwrap_err();
+        assert!(err.to_string().contains("underscores and `.`"));
+    }
+
+    #[test]
+    fn test_validate_rejects_from_file_with_other_rules() {
+        let rule = |rename_all| StructAttrs {
+            rename_all,
+            ..Default::default()
+        };
+        let from_file: Field = parse_quote!(#[conf(from_file)] pub api_key: String);
+
+        let err = validate_field(from_file.clone(), &rule(RenameRule::Kebab)).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "`from_file` would read `api-key_FILE`, which does not follow \
+             `rename_all = \"kebab-case\"`; set the variable's `name` explicitly"
+        );
+        for rename_all in [RenameRule::Dot, RenameRule::Snake, RenameRule::Camel] {
+            assert!(validate_field(from_file.clone(), &rule(rename_all)).is_err());
+        }
+        for rename_all in [RenameRule::ScreamingSnake, RenameRule::Upper] {
+            assert!(validate_field(from_file.clone(), &rule(rename_all)).is_ok());
+        }
+
+        let named: Field = parse_quote!(#[conf(from_file, name = "API_KEY")] pub api_key: String);
+        assert!(validate_field(named, &rule(RenameRule::Kebab)).is_ok());
+    }
+
+    #[test]
+    fn test_validate_requires_posix_explicit_names() {
+        let check = |field: Field| validate_field(field, &StructAttrs::default());
+        assert!(
+            check(parse_quote!(#[conf(name = "APP_PORT", alias = "PORT")] pub port: u16)).is_ok()
+        );
+
+        let err = check(parse_quote!(#[conf(name = "my-app.port")] pub port: u16)).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "`my-app.port` is not a valid environment variable name \
+             (expected letters, digits and underscores, not starting with a digit)"
+        );
+        let err = check(parse_quote!(#[conf(alias = "OLD-NAME")] pub port: u16)).unwrap_err();
+        assert!(err.to_string().contains("`OLD-NAME`"));
+        let err = check(parse_quote!(#[conf(deprecated_name = "2FA")] pub port: u16)).unwrap_err();
+        assert!(err.to_string().contains("`2FA`"));
+
+        // The prefix is part of every name
+        let struct_attrs = StructAttrs {
+            prefix: "MY-APP_".to_string(),
+            ..Default::default()
+        };
+        let field: Field = parse_quote!(#[conf(name = "PORT")] pub port: u16);
+        let err = validate_field(field, &struct_attrs).unwrap_err();
+        assert!(err.to_string().contains("`MY-APP_PORT`"));
+    }
+
+    #[test]
+    fn test_validate_allows_separator_only_in_derived_names() {
+        for (rename_all, name) in [
+            (RenameRule::Kebab, "api-key"),
+            (RenameRule::ScreamingKebab, "API-KEY"),
+            (RenameRule::Dot, "api.key"),
+        ] {
+            let struct_attrs = StructAttrs {
+                rename_all,
+                ..Default::default()
+            };
+            assert!(validate_field(parse_quote!(pub api_key: String), &struct_attrs).is_ok());
+
+            let field: Field = parse_quote!(#[conf(alias = #name)] pub token: String);
+            let err = validate_field(field, &struct_attrs).unwrap_err();
+            assert!(err.to_string().contains(&format!("`{}`", name)));
+
+            let field: Field = parse_quote!(#[conf(name = #name)] pub token: String);
+            assert!(validate_field(field, &struct_attrs).is_err());
+        }
+    }
+
+    #[test]
+    fn test_validate_checks_file_variants() {
+        let field: Field = parse_quote!(#[conf(from_file, alias = "OLD_KEY")] pub api_key: String);
+        assert!(validate_field(field, &StructAttrs::default()).is_ok());
+
+        // An explicit kebab-case name is rejected along with its `_FILE` variant
+        let struct_attrs = StructAttrs {
+            rename_all: RenameRule::Kebab,
+            ..Default::default()
+        };
+        let field: Field = parse_quote!(#[conf(from_file, name = "api-key")] pub api_key: String);
+        let err = validate_field(field, &struct_attrs).unwrap_err();
+        let messages: Vec<_> = err.into_iter().map(|e| e.to_string()).collect();
+        assert_eq!(messages.len(), 2);
+        assert!(messages[1].starts_with("`api-key_FILE` is not a valid environment variable name"));
+    }
+}
@@ -0,0 +1,162 @@
+//! Code generation for redacting `Debug` and `Display` impls.
+//!
+//! Secret fields (`#[conf(secret)]`, `from_file` fields unless marked
+//! `secret = false`, and `serviceconf::Secret<T>` fields) are printed as `***`
+//! so configuration can be logged safely.
+
+use crate::attrs::FieldAttrs;
+use crate::is_option_type;
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::ext::IdentExt;
+use syn::{Field, Ident};
+
+/// Placeholder printed instead of a secret value.
+const MASK: &str = "***";
+
+/// Placeholder printed by `Display` for `Option<T>` fields that are `None`.
+const UNSET: &str = "<unset>";
+
+/// `Debug` impl printing loaded fields like `#[derive(Debug)]`, with secret
+/// values masked.
+///
+/// Skipped fields are runtime state rather than configuration and are left out,
+/// which is shown as `..` in the output.
+pub fn debug_impl(
+    struct_name: &Ident,
+    fields: &[&Field],
+    attrs: &[FieldAttrs],
+    has_skipped: bool,
+) -> TokenStream {
+    let name = struct_name.to_string();
+    let entries = fields.iter().zip(attrs).map(|(field, attrs)| {
+        let ident = &field.ident;
+        let label = ident.as_ref().unwrap().unraw().to_string();
+        if attrs.is_secret() {
+            quote! { .field(#label, &::std::format_args!(#MASK)) }
+        } else if attrs.secret.is_none() {
+            // Secret depending on the type, which only the compiler can tell
+            let secret = crate::secret_expr(&field.ty, attrs);
+            quote! {
+                .field(#label, if #secret {
+                    &::serviceconf::de::Masked as &dyn ::std::fmt::Debug
+                } else {
+                    &self.#ident
+                })
+            }
+        } else {
+            quote! { .field(#label, &self.#ident) }
+        }
+    });
+    let finish = if has_skipped {
+        quote! { finish_non_exhaustive }
+    } else {
+        quote! { finish }
+    };
+
+    quote! {
+        impl ::std::fmt::Debug for #struct_name {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                f.debug_struct(#name)
+                    #(#entries)*
+                    .#finish()
+            }
+        }
+    }
+}
+
+/// `Display` impl writing one `NAME=value` line per loaded field, with secret
+/// values masked.
+///
+/// Values are rendered with the field's `with` module when it has one and with
+/// `Debug` otherwise; `Option<T>` fields that are `None` print as `<unset>`.
+pub fn display_impl(
+    struct_name: &Ident,
+    fields: &[&Field],
+    attrs: &[FieldAttrs],
+    env_var_names: &[String],
+) -> TokenStream {
+    let lines = fields.iter().zip(attrs).zip(env_var_names).enumerate().map(
+        |(i, ((field, attrs), env_var_name))| {
+            let ident = &field.ident;
+            let separator = if i == 0 { "" } else { "\n" };
+            let label = format!("{}{}=", separator, env_var_name);
+
+            let write_value = |value: TokenStream| {
+                let write = if let Some(module) = &attrs.with {
+                    quote! { f.write_str(&#module::to_env_string(#value))?; }
+                } else {
+                    quote! { ::std::write!(f, "{:?}", #value)?; }
+                };
+                if attrs.is_secret() {
+                    quote! { f.write_str(#MASK)?; }
+                } else if attrs.secret.is_none() {
+                    // Secret depending on the type, which only the compiler can tell
+                    let secret = crate::secret_expr(&field.ty, attrs);
+                    quote! {
+                        if #secret {
+                            f.write_str(#MASK)?;
+                        } else {
+                            #write
+                        }
+                    }
+                } else {
+                    write
+                }
+            };
+            let value = if is_option_type(&field.ty) {
+                let write_inner = write_value(quote! { __value });
+                quote! {
+                    match &self.#ident {
+                        ::std::option::Option::Some(__value) => { #write_inner }
+                        ::std::option::Option::None => f.write_str(#UNSET)?,
+                    }
+                }
+            } else {
+                write_value(quote! { &self.#ident })
+            };
+
+            quote! {
+                f.write_str(#label)?;
+                #value
+            }
+        },
+    );
+
+    quote! {
+        impl ::std::fmt::Display for #struct_name {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                #(#lines)*
+                ::std::result::Result::Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::parse_quote;
+
+    #[test]
+    fn test_debug_masks_secret_fields() {
+        let fields: [Field; 2] = [
+            parse_quote!(pub port: u16),
+            parse_quote!(#[conf(from_file)] pub api_key: String),
+        ];
+        let attrs: Vec<_> = fields
+            .iter()
+            .map(|field| FieldAttrs::from_field(field).unwrap())
+            .collect();
+        let fields: Vec<_> = fields.iter().collect();
+
+        let tokens = debug_impl(&parse_quote!(Config), &fields, &attrs, false).to_string();
+        assert!(tokens.contains("& self . port"));
+        assert!(!tokens.contains("self . api_key"));
+        assert!(tokens.contains("\"***\""));
+        assert!(tokens.contains(". finish ()"));
+
+        let tokens = debug_impl(&parse_quote!(Config), &fields, &attrs, true).to_string();
+        assert!(tokens.contains(". finish_non_exhaustive ()"));
+    }
+}
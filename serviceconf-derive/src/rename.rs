@@ -0,0 +1,171 @@
+//! Naming strategies for `#[conf(rename_all = "...")]`.
+//!
+//! Converts snake_case field names into environment variable names following
+//! the selected convention.
+
+/// Naming convention applied to field names when deriving environment variable names.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RenameRule {
+    /// `SCREAMING_SNAKE_CASE` (the default)
+    #[default]
+    ScreamingSnake,
+    /// `snake_case`
+    Snake,
+    /// `lowercase`
+    Lower,
+    /// `UPPERCASE`
+    Upper,
+    /// `PascalCase`
+    Pascal,
+    /// `camelCase`
+    Camel,
+    /// `kebab-case`
+    Kebab,
+    /// `SCREAMING-KEBAB-CASE`
+    ScreamingKebab,
+    /// `dot.case`
+    Dot,
+}
+
+impl RenameRule {
+    /// Every supported rule with its attribute spelling.
+    const ALL: &'static [(&'static str, RenameRule)] = &[
+        ("SCREAMING_SNAKE_CASE", RenameRule::ScreamingSnake),
+        ("snake_case", RenameRule::Snake),
+        ("lowercase", RenameRule::Lower),
+        ("UPPERCASE", RenameRule::Upper),
+        ("PascalCase", RenameRule::Pascal),
+        ("camelCase", RenameRule::Camel),
+        ("kebab-case", RenameRule::Kebab),
+        ("SCREAMING-KEBAB-CASE", RenameRule::ScreamingKebab),
+        ("dot.case", RenameRule::Dot),
+    ];
+
+    /// Parse a rule from its attribute spelling (e.g. `"kebab-case"`).
+    pub fn from_lit(lit: &syn::LitStr) -> syn::Result<Self> {
+        let value = lit.value();
+        Self::ALL
+            .iter()
+            .find(|(name, _)| *name == value)
+            .map(|(_, rule)| *rule)
+            .ok_or_else(|| {
+                let expected = Self::ALL
+                    .iter()
+                    .map(|(name, _)| format!("\"{}\"", name))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                syn::Error::new_spanned(
+                    lit,
+                    format!(
+                        "unknown rename_all rule \"{}\", expected one of {}",
+                        value, expected
+                    ),
+                )
+            })
+    }
+
+    /// Attribute spelling of the rule (e.g. `"kebab-case"`).
+    pub fn as_str(self) -> &'static str {
+        Self::ALL
+            .iter()
+            .find(|(_, rule)| *rule == self)
+            .map(|(name, _)| *name)
+            .unwrap()
+    }
+
+    /// Whether derived names are upper snake case, matching the `_FILE` suffix
+    /// read by `from_file` fields.
+    pub fn matches_file_suffix(self) -> bool {
+        matches!(self, RenameRule::ScreamingSnake | RenameRule::Upper)
+    }
+
+    /// Word separator of rules producing non-POSIX environment variable names,
+    /// `None` for rules whose names are POSIX-compatible.
+    pub fn separator(self) -> Option<char> {
+        match self {
+            RenameRule::Kebab | RenameRule::ScreamingKebab => Some('-'),
+            RenameRule::Dot => Some('.'),
+            _ => None,
+        }
+    }
+
+    /// Apply the rule to a snake_case field name.
+    pub fn apply(self, field: &str) -> String {
+        let words = field.split('_').filter(|w| !w.is_empty());
+        match self {
+            RenameRule::ScreamingSnake | RenameRule::Upper => field.to_ascii_uppercase(),
+            RenameRule::Snake | RenameRule::Lower => field.to_ascii_lowercase(),
+            RenameRule::Pascal => words.map(capitalize).collect(),
+            RenameRule::Camel => words
+                .enumerate()
+                .map(|(i, w)| {
+                    if i == 0 {
+                        w.to_ascii_lowercase()
+                    } else {
+                        capitalize(w)
+                    }
+                })
+                .collect(),
+            RenameRule::Kebab => field.to_ascii_lowercase().replace('_', "-"),
+            RenameRule::ScreamingKebab => field.to_ascii_uppercase().replace('_', "-"),
+            RenameRule::Dot => field.to_ascii_lowercase().replace('_', "."),
+        }
+    }
+}
+
+/// Uppercase the first character of a word and lowercase the rest.
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => {
+            first.to_ascii_uppercase().to_string() + &chars.as_str().to_ascii_lowercase()
+        }
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::parse_quote;
+
+    #[test]
+    fn test_as_str_round_trips() {
+        for (name, rule) in RenameRule::ALL {
+            assert_eq!(rule.as_str(), *name);
+        }
+    }
+
+    #[test]
+    fn test_apply_rules() {
+        let field = "database_url";
+        assert_eq!(RenameRule::ScreamingSnake.apply(field), "DATABASE_URL");
+        assert_eq!(RenameRule::Snake.apply(field), "database_url");
+        assert_eq!(RenameRule::Lower.apply(field), "database_url");
+        assert_eq!(RenameRule::Upper.apply(field), "DATABASE_URL");
+        assert_eq!(RenameRule::Pascal.apply(field), "DatabaseUrl");
+        assert_eq!(RenameRule::Camel.apply(field), "databaseUrl");
+        assert_eq!(RenameRule::Kebab.apply(field), "database-url");
+        assert_eq!(RenameRule::ScreamingKebab.apply(field), "DATABASE-URL");
+        assert_eq!(RenameRule::Dot.apply(field), "database.url");
+    }
+
+    #[test]
+    fn test_separator() {
+        assert_eq!(RenameRule::ScreamingSnake.separator(), None);
+        assert_eq!(RenameRule::Camel.separator(), None);
+        assert_eq!(RenameRule::Kebab.separator(), Some('-'));
+        assert_eq!(RenameRule::ScreamingKebab.separator(), Some('-'));
+        assert_eq!(RenameRule::Dot.separator(), Some('.'));
+    }
+
+    #[test]
+    fn test_from_lit() {
+        let lit: syn::LitStr = parse_quote!("kebab-case");
+        assert_eq!(RenameRule::from_lit(&lit).unwrap(), RenameRule::Kebab);
+
+        let lit: syn::LitStr = parse_quote!("Kebab-Case");
+        let err = RenameRule::from_lit(&lit).unwrap_err();
+        assert!(err.to_string().contains("unknown rename_all rule"));
+    }
+}
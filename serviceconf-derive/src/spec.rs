@@ -0,0 +1,271 @@
+//! Code generation for static variable metadata.
+//!
+//! Builds the `serviceconf::EnvVarSpec` entry returned by `env_vars()` for each
+//! loaded field, and the associated constant holding its variable name.
+
+use crate::attrs::{FieldAttrs, StructAttrs, Validation};
+use crate::{defaults, is_option_type, names};
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote, ToTokens};
+use syn::ext::IdentExt;
+use syn::{Expr, Field, Lit, Meta};
+
+/// Expression building the `EnvVarSpec` of a field.
+pub fn env_var_spec(field: &Field, attrs: &FieldAttrs, struct_attrs: &StructAttrs) -> TokenStream {
+    let field_name = field.ident.as_ref().unwrap().unraw().to_string();
+    let name = names::env_var_name(field, attrs, struct_attrs);
+    let prefixed = |name: &str| format!("{}{}", struct_attrs.prefix, name);
+    let aliases = attrs.aliases.iter().map(|alias| prefixed(alias));
+    let deprecated_names = attrs.deprecated_names.iter().map(|d| prefixed(&d.name));
+    let type_name = defaults::render_tokens(&field.ty.to_token_stream());
+    let default = defaults::describe(attrs);
+    let required = default.is_none() && !is_option_type(&field.ty);
+    let default = option(default);
+    let env_default = option(defaults::env_syntax(attrs));
+    let env_default_fn = match env_default_fn(field, attrs) {
+        Some(render) => quote! { ::std::option::Option::Some(#render) },
+        None => quote! { ::std::option::Option::None },
+    };
+    let from_file = attrs.from_file;
+    let validations = attrs.validations.iter().map(validation);
+    let deserializer = option(match (&attrs.deserializer, &attrs.with) {
+        (Some(func), _) => Some(defaults::render_tokens(&func.to_token_stream())),
+        (None, Some(module)) => Some(format!(
+            "{}::parse",
+            defaults::render_tokens(&module.to_token_stream())
+        )),
+        (None, None) => None,
+    });
+    let secret = crate::secret_expr(&field.ty, attrs);
+    let doc = doc_comment(field);
+    let group = option(attrs.group.clone());
+
+    quote! {
+        ::serviceconf::EnvVarSpec {
+            field: #field_name,
+            name: #name,
+            aliases: &[#(#aliases),*],
+            deprecated_names: &[#(#deprecated_names),*],
+            type_name: #type_name,
+            required: #required,
+            default: #default,
+            env_default: #env_default,
+            env_default_fn: #env_default_fn,
+            from_file: #from_file,
+            validations: &[#(#validations),*],
+            deserializer: #deserializer,
+            secret: #secret,
+            doc: #doc,
+            group: #group,
+        }
+    }
+}
+
+/// Closure rendering a computed default with the field's `with` module, for
+/// defaults [`defaults::env_syntax`] cannot write out.
+///
+/// `None` without `with`, for `default_str` (already in environment variable
+/// syntax), and for `default_from` defaults, which depend on other fields.
+fn env_default_fn(field: &Field, attrs: &FieldAttrs) -> Option<TokenStream> {
+    let module = attrs.with.as_ref()?;
+    if attrs.default_str.is_some() || !attrs.default_from.is_empty() {
+        return None;
+    }
+    let field_type = &field.ty;
+    let default = match (&attrs.default_fn, &attrs.default) {
+        (Some(func), _) => quote! { #func() },
+        (None, Some(Some(value))) => quote! { #value },
+        (None, Some(None)) => quote! { <#field_type as ::std::default::Default>::default() },
+        (None, None) => return None,
+    };
+    Some(quote! {
+        || {
+            let __default: #field_type = #default;
+            #module::to_env_string(&__default)
+        }
+    })
+}
+
+/// Expression building the `serviceconf::Validation` describing a rule, with
+/// values rendered as written.
+fn validation(validation: &Validation) -> TokenStream {
+    let render = |tokens: &dyn ToTokens| defaults::render_tokens(&tokens.to_token_stream());
+    match validation {
+        Validation::Range { min, max } => {
+            let min = option(min.as_ref().map(|min| render(min)));
+            let max = option(max.as_ref().map(|max| render(max)));
+            quote! { ::serviceconf::Validation::Range { min: #min, max: #max } }
+        }
+        Validation::MinLen(len) => quote! { ::serviceconf::Validation::MinLen(#len) },
+        Validation::MaxLen(len) => quote! { ::serviceconf::Validation::MaxLen(#len) },
+        Validation::NonEmpty => quote! { ::serviceconf::Validation::NonEmpty },
+        Validation::OneOf(values) => {
+            let values = values.iter().map(|value| render(value));
+            quote! { ::serviceconf::Validation::OneOf(&[#(#values),*]) }
+        }
+        Validation::Regex(pattern) => {
+            let pattern = pattern.value();
+            quote! { ::serviceconf::Validation::Regex(#pattern) }
+        }
+        Validation::Custom(func) => {
+            let func = render(func);
+            quote! { ::serviceconf::Validation::Custom(#func) }
+        }
+    }
+}
+
+/// Associated constant named after the field (upper-cased) holding its
+/// primary environment variable name, e.g. `Config::DATABASE_URL`.
+pub fn name_const(field: &Field, attrs: &FieldAttrs, struct_attrs: &StructAttrs) -> TokenStream {
+    let ident = field.ident.as_ref().unwrap();
+    let field_name = ident.unraw().to_string();
+    let const_name = format_ident!("{}", field_name.to_ascii_uppercase(), span = ident.span());
+    let name = names::env_var_name(field, attrs, struct_attrs);
+    let doc = format!(
+        " Environment variable the `{}` field is read from",
+        field_name
+    );
+
+    quote! {
+        #[doc = #doc]
+        pub const #const_name: &'static str = #name;
+    }
+}
+
+/// `Some("...")` or `None` tokens for an optional string.
+fn option(value: Option<String>) -> TokenStream {
+    match value {
+        Some(value) => quote! { ::std::option::Option::Some(#value) },
+        None => quote! { ::std::option::Option::None },
+    }
+}
+
+/// The field's `///` doc comment, with the leading space of each line removed
+/// and surrounding blank lines trimmed.
+fn doc_comment(field: &Field) -> String {
+    let lines: Vec<String> = field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("doc"))
+        .filter_map(|attr| match &attr.meta {
+            Meta::NameValue(meta) => match &meta.value {
+                Expr::Lit(expr) => match &expr.lit {
+                    Lit::Str(lit) => Some(lit.value()),
+                    _ => None,
+                },
+                _ => None,
+            },
+            _ => None,
+        })
+        .flat_map(|doc| {
+            doc.split('\n')
+                .map(|line| {
+                    line.strip_prefix(' ')
+                        .unwrap_or(line)
+                        .trim_end()
+                        .to_string()
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    lines.join("\n").trim_matches('\n').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::parse_quote;
+
+    #[test]
+    fn test_doc_comment() {
+        let field: Field = parse_quote! {
+            /// Port the HTTP server listens on.
+            ///
+            /// Must be above 1024.
+            #[conf(default = 8080)]
+            pub port: u16
+        };
+        assert_eq!(
+            doc_comment(&field),
+            "Port the HTTP server listens on.\n\nMust be above 1024."
+        );
+
+        let field: Field = parse_quote!(pub port: u16);
+        assert_eq!(doc_comment(&field), "");
+    }
+
+    #[test]
+    fn test_env_var_spec() {
+        let field: Field = parse_quote! {
+            #[conf(alias = "DB_URL", deserializer = parse_url, default_str = "sqlite::memory:")]
+            pub database_url: Url
+        };
+        let attrs = FieldAttrs::from_field(&field).unwrap();
+        let struct_attrs = StructAttrs {
+            prefix: "APP_".to_string(),
+            ..Default::default()
+        };
+        let tokens = env_var_spec(&field, &attrs, &struct_attrs).to_string();
+        assert!(tokens.contains("name : \"APP_DATABASE_URL\""));
+        assert!(tokens.contains("aliases : & [\"APP_DB_URL\"]"));
+        assert!(tokens.contains("type_name : \"Url\""));
+        assert!(tokens.contains("required : false"));
+        assert!(tokens
+            .contains("env_default : :: std :: option :: Option :: Some (\"sqlite::memory:\")"));
+        assert!(
+            tokens.contains("deserializer : :: std :: option :: Option :: Some (\"parse_url\")")
+        );
+    }
+
+    #[test]
+    fn test_env_default_fn() {
+        let field: Field = parse_quote! {
+            #[conf(with = secs, default = Duration::from_secs(60))]
+            pub timeout: Duration
+        };
+        let attrs = FieldAttrs::from_field(&field).unwrap();
+        let render = env_default_fn(&field, &attrs).unwrap().to_string();
+        assert!(render.contains("let __default : Duration = Duration :: from_secs (60)"));
+        assert!(render.contains("secs :: to_env_string (& __default)"));
+
+        let field: Field = parse_quote! {
+            #[conf(with = secs, default)]
+            pub timeout: Duration
+        };
+        let attrs = FieldAttrs::from_field(&field).unwrap();
+        let render = env_default_fn(&field, &attrs).unwrap().to_string();
+        assert!(render.contains("< Duration as :: std :: default :: Default > :: default ()"));
+
+        // Already in env syntax, or no `with` module to render it
+        for field in [
+            parse_quote!(#[conf(with = secs, default_str = "60")] pub timeout: Duration),
+            parse_quote!(#[conf(with = secs)] pub timeout: Duration),
+            parse_quote!(#[conf(default = Duration::from_secs(60))] pub timeout: Duration),
+        ] {
+            let attrs = FieldAttrs::from_field(&field).unwrap();
+            assert!(env_default_fn(&field, &attrs).is_none());
+        }
+    }
+
+    #[test]
+    fn test_validation() {
+        let field: Field = parse_quote! {
+            #[conf(range(min = 1024), one_of = ["debug", "info"], max_len = 8)]
+            pub level: String
+        };
+        let attrs = FieldAttrs::from_field(&field).unwrap();
+        let tokens: Vec<_> = attrs
+            .validations
+            .iter()
+            .map(|v| validation(v).to_string())
+            .collect();
+        assert_eq!(
+            tokens,
+            [
+                ":: serviceconf :: Validation :: Range { min : :: std :: option :: Option :: Some (\"1024\") , max : :: std :: option :: Option :: None }",
+                ":: serviceconf :: Validation :: OneOf (& [\"\\\"debug\\\"\" , \"\\\"info\\\"\"])",
+                ":: serviceconf :: Validation :: MaxLen (8usize)",
+            ]
+        );
+    }
+}
@@ -0,0 +1,96 @@
+//! Code generation for declarative validation rules.
+//!
+//! Turns `range`, `min_len`, `max_len`, `non_empty`, `one_of`, `regex` and
+//! `validate` attributes into checks that run after a field has been loaded,
+//! and struct-level `validate` functions into checks on the built struct.
+
+use crate::attrs::{FieldAttrs, Validation};
+use proc_macro2::TokenStream;
+use quote::{quote, quote_spanned};
+use syn::{Ident, Type};
+
+/// Statements checking the loaded value bound to `binding`.
+///
+/// `value_type` is the field type, or the inner type for `Option<T>` fields,
+/// whose rules only apply when a value is present. Values of fields for which
+/// the `secret` expression is true are left out of the error messages. Returns
+/// an empty token stream for fields without rules.
+pub fn checks(
+    attrs: &FieldAttrs,
+    env_var_name: &str,
+    binding: &Ident,
+    value_type: &Type,
+    is_option: bool,
+    secret: &TokenStream,
+) -> TokenStream {
+    if attrs.validations.is_empty() {
+        return TokenStream::new();
+    }
+
+    let checks = attrs.validations.iter().map(|rule| match rule {
+        Validation::Range { min, max } => {
+            let bound = |expr: &Option<Box<syn::Expr>>| match expr {
+                Some(expr) => quote! { ::std::option::Option::Some(#expr) },
+                None => quote! { ::std::option::Option::None },
+            };
+            let (min, max) = (bound(min), bound(max));
+            quote! {
+                ::serviceconf::validate::range::<#value_type>(
+                    #env_var_name, #secret, __value, #min, #max,
+                )?;
+            }
+        }
+        Validation::MinLen(min) => quote! {
+            ::serviceconf::validate::min_len(#env_var_name, __value, #min)?;
+        },
+        Validation::MaxLen(max) => quote! {
+            ::serviceconf::validate::max_len(#env_var_name, __value, #max)?;
+        },
+        Validation::NonEmpty => quote! {
+            ::serviceconf::validate::non_empty(#env_var_name, __value)?;
+        },
+        Validation::OneOf(values) => quote! {
+            ::serviceconf::validate::one_of(#env_var_name, __value, &[#(#values),*])?;
+        },
+        Validation::Custom(func) => {
+            // Rule named after the function, e.g. `check_pool_size`
+            let rule = func.segments.last().unwrap().ident.to_string();
+            quote! {
+                ::serviceconf::validate::custom(#env_var_name, #secret, #rule, (#func)(__value))?;
+            }
+        }
+        // Spanned so a missing `regex` feature is reported at the pattern
+        Validation::Regex(pattern) => quote_spanned! {pattern.span()=>
+            ::serviceconf::validate::regex(#env_var_name, __value, #pattern)?;
+        },
+    });
+
+    if is_option {
+        quote! {
+            if let ::std::option::Option::Some(__value) = &#binding {
+                #(#checks)*
+            }
+        }
+    } else {
+        quote! {
+            {
+                let __value = &#binding;
+                #(#checks)*
+            }
+        }
+    }
+}
+
+/// Expressions checking the fully built struct bound to `config` with the
+/// struct-level `validate` functions, each evaluating to
+/// `Result<(), ServiceConfError>`.
+pub fn struct_checks(validators: &[syn::Path], config: &Ident) -> Vec<TokenStream> {
+    validators
+        .iter()
+        .map(|func| {
+            quote! {
+                (#func)(&#config).map_err(::serviceconf::ServiceConfError::invalid_config::<Self>)
+            }
+        })
+        .collect()
+}
@@ -1,10 +1,12 @@
 //! Compile-fail tests to verify error messages
 //!
 //! These tests ensure that invalid attribute combinations produce clear,
-//! helpful error messages instead of confusing syntax errors.
+//! helpful error messages instead of confusing syntax errors, and that valid
+//! inputs close to them (in `tests/ui-pass`) keep compiling.
 
 #[test]
 fn ui_tests() {
     let t = trybuild::TestCases::new();
     t.compile_fail("tests/ui/*.rs");
+    t.pass("tests/ui-pass/*.rs");
 }
@@ -0,0 +1,34 @@
+use serviceconf::ServiceConf;
+
+mod vault {
+    /// Unrelated type that happens to be called `Secret`
+    pub struct Secret<T>(pub T);
+
+    impl<T: std::str::FromStr> std::str::FromStr for Secret<T> {
+        type Err = T::Err;
+
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            s.parse().map(Secret)
+        }
+    }
+}
+
+use vault::Secret;
+
+fn parse_token(s: &str) -> Result<vault::Secret<String>, std::convert::Infallible> {
+    Ok(vault::Secret(s.to_string()))
+}
+
+#[derive(ServiceConf)]
+#[allow(dead_code)]
+struct Config {
+    pub api_key: Secret<String>,
+
+    #[conf(deserializer = parse_token)]
+    pub token: vault::Secret<String>,
+
+    #[conf(deserializer = parse_token)]
+    pub refresh_token: Option<Secret<String>>,
+}
+
+fn main() {}
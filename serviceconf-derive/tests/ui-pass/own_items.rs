@@ -0,0 +1,28 @@
+use serviceconf::{ServiceConf, ServiceConfSpec};
+
+#[derive(ServiceConf)]
+#[allow(dead_code)]
+struct Config {
+    pub port: u16,
+}
+
+/// Inherent items named like the spec methods and the name constants: the
+/// derive adds neither by default, so these compile without clashing
+impl Config {
+    const PORT: u16 = 8080;
+
+    fn env_vars() -> Vec<&'static str> {
+        vec!["PORT"]
+    }
+
+    fn env_example() -> &'static str {
+        "PORT=8080"
+    }
+}
+
+fn main() {
+    let _ = Config::PORT;
+    let _ = Config::env_vars();
+    let _ = Config::env_example();
+    let _ = <Config as ServiceConfSpec>::env_vars();
+}
@@ -0,0 +1,20 @@
+// This test verifies that two fields reading the same environment variable,
+// directly or through a `_FILE` variant, are rejected at compile time
+
+use serviceconf::ServiceConf;
+
+#[derive(ServiceConf)]
+#[conf(prefix = "APP_")]
+struct Config {
+    pub port: u16,
+
+    #[conf(name = "PORT")]
+    pub http_port: u16,
+
+    #[conf(from_file)]
+    pub api_key: String,
+
+    pub api_key_file: String,
+}
+
+fn main() {}
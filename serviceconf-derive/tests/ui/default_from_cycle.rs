@@ -0,0 +1,15 @@
+// This test verifies that default_from dependency cycles are rejected
+// at compile time
+
+use serviceconf::ServiceConf;
+
+#[derive(ServiceConf)]
+struct Config {
+    #[conf(default_from = "admin_port")]
+    pub port: u16,
+
+    #[conf(default_from = "port")]
+    pub admin_port: u16,
+}
+
+fn main() {}
@@ -0,0 +1,15 @@
+// This test verifies that a custom error type must be convertible from
+// ServiceConfError, with the error pointing at the attribute
+
+use serviceconf::ServiceConf;
+
+#[derive(Debug)]
+pub struct AppError;
+
+#[derive(ServiceConf)]
+#[conf(error = AppError)]
+struct Config {
+    pub api_key: String,
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+// This test verifies that `from_file` is rejected on names derived by a
+// rename_all rule the `_FILE` suffix does not follow
+
+use serviceconf::ServiceConf;
+
+#[derive(ServiceConf)]
+#[conf(rename_all = "kebab-case")]
+struct Config {
+    #[conf(from_file)]
+    pub api_key: String,
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+// This test verifies that a default_str which cannot be parsed as the field's
+// standard library type is rejected at compile time
+
+use serviceconf::ServiceConf;
+
+#[derive(ServiceConf)]
+struct Config {
+    #[conf(default_str = "eighty")]
+    pub port: u16,
+}
+
+fn main() {}
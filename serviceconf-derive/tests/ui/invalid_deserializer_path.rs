@@ -0,0 +1,12 @@
+// This test verifies that a malformed deserializer path in string form is
+// reported at the string literal instead of panicking the macro
+
+use serviceconf::ServiceConf;
+
+#[derive(ServiceConf)]
+struct Config {
+    #[conf(deserializer = "serde_json::")]
+    pub tags: Vec<String>,
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+// This test verifies that names which are not legal POSIX environment variable
+// names are rejected at compile time
+
+use serviceconf::ServiceConf;
+
+#[derive(ServiceConf)]
+#[conf(prefix = "MY-APP_")]
+struct Config {
+    pub port: u16,
+}
+
+fn main() {}
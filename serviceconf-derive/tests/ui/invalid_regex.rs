@@ -0,0 +1,11 @@
+// This test verifies that an invalid regex pattern is rejected at compile time
+
+use serviceconf::ServiceConf;
+
+#[derive(ServiceConf)]
+struct Config {
+    #[conf(regex = "^[a-z+$")]
+    pub region: String,
+}
+
+fn main() {}
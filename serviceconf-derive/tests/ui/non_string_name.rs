@@ -0,0 +1,12 @@
+// This test verifies that a non-string value for `name` is rejected with a
+// span pointing at the value
+
+use serviceconf::ServiceConf;
+
+#[derive(ServiceConf)]
+struct Config {
+    #[conf(name = 42)]
+    pub port: u16,
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+// This test verifies that the regex validation reports a clear error when
+// the `regex` feature of serviceconf is not enabled
+
+use serviceconf::ServiceConf;
+
+#[derive(ServiceConf)]
+struct Config {
+    #[conf(regex = "^[a-z]+$")]
+    pub region: String,
+}
+
+fn main() {}
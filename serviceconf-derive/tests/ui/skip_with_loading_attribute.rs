@@ -0,0 +1,12 @@
+// This test verifies that attributes controlling how a field is loaded are
+// rejected on fields marked `skip`
+
+use serviceconf::ServiceConf;
+
+#[derive(ServiceConf)]
+struct Config {
+    #[conf(skip, name = "CLIENT")]
+    pub client: Option<String>,
+}
+
+fn main() {}
@@ -0,0 +1,14 @@
+// This test verifies that cross-field constraints referencing a field that
+// does not exist are rejected at compile time
+
+use serviceconf::ServiceConf;
+
+#[derive(ServiceConf)]
+struct Config {
+    #[conf(requires = "tls_key")]
+    pub tls_cert: Option<String>,
+
+    pub tls_private_key: Option<String>,
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+// This test verifies that a misspelled field-level attribute is rejected
+// with a suggestion for the closest known attribute
+
+use serviceconf::ServiceConf;
+
+#[derive(ServiceConf)]
+struct Config {
+    #[conf(form_file)]
+    pub api_key: String,
+}
+
+fn main() {}
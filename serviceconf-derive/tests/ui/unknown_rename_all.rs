@@ -0,0 +1,12 @@
+// This test verifies that an unknown rename_all rule produces a clear error
+// instead of silently falling back to the default naming convention
+
+use serviceconf::ServiceConf;
+
+#[derive(ServiceConf)]
+#[conf(rename_all = "Kebab-Case")]
+struct Config {
+    pub api_key: String,
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+// This test verifies that a misspelled struct-level attribute is rejected
+// with a suggestion for the closest known attribute
+
+use serviceconf::ServiceConf;
+
+#[derive(ServiceConf)]
+#[conf(prefx = "APP_")]
+struct Config {
+    pub port: u16,
+}
+
+fn main() {}
@@ -7,44 +7,400 @@
 //! which is the primary feature distinguishing this library from other environment
 //! configuration solutions.
 
-use crate::error::ServiceConfError;
+use crate::deprecation::{self, DeprecatedUsage};
+use crate::error::{ServiceConfError, HIDDEN_MESSAGE};
+use crate::provenance::{NameKind, Source};
+use crate::secret::Secret;
+use std::cell::RefCell;
 use std::env;
 use std::fs;
+use std::marker::PhantomData;
 use std::str::FromStr;
+use zeroize::Zeroize;
+
+/// Description of the environment variables a single field is read from
+///
+/// Built by macro-generated code. Names are tried in order: the primary name,
+/// then each alias, then each deprecated name. When `from_file` is enabled, every
+/// name is immediately followed by its `{NAME}_FILE` variant.
+#[doc(hidden)]
+#[derive(Debug, Clone, Copy)]
+pub struct EnvVar<'a> {
+    /// Primary environment variable name
+    pub name: &'a str,
+    /// Fallback names tried after the primary name
+    pub aliases: &'a [&'a str],
+    /// Retired names that are still accepted but emit a warning when used
+    pub deprecated: &'a [Deprecated<'a>],
+    /// Whether `{NAME}_FILE` variants are consulted
+    pub from_file: bool,
+    /// Whether the value is a secret that must not appear in error messages
+    pub secret: bool,
+    /// Whether parse errors include a truncated snippet of a non-secret value
+    pub show_value: bool,
+    /// Where the source of the loaded value is recorded, if anywhere
+    pub recorder: Option<&'a SourceRecorder>,
+}
+
+/// Deprecated environment variable name with an optional migration note
+#[doc(hidden)]
+#[derive(Debug, Clone, Copy)]
+pub struct Deprecated<'a> {
+    /// Deprecated environment variable name
+    pub name: &'a str,
+    /// Note included in the warning
+    pub note: Option<&'a str>,
+}
+
+impl<'a> Deprecated<'a> {
+    /// Create a deprecated name entry
+    pub const fn new(name: &'a str, note: Option<&'a str>) -> Self {
+        Self { name, note }
+    }
+}
+
+impl<'a> EnvVar<'a> {
+    /// Create a lookup for a single environment variable name
+    pub const fn new(name: &'a str) -> Self {
+        Self {
+            name,
+            aliases: &[],
+            deprecated: &[],
+            from_file: false,
+            secret: false,
+            show_value: false,
+            recorder: None,
+        }
+    }
+
+    /// Set fallback names tried after the primary name
+    pub const fn aliases(mut self, aliases: &'a [&'a str]) -> Self {
+        self.aliases = aliases;
+        self
+    }
+
+    /// Set deprecated names tried after the aliases
+    pub const fn deprecated(mut self, deprecated: &'a [Deprecated<'a>]) -> Self {
+        self.deprecated = deprecated;
+        self
+    }
+
+    /// Enable or disable the `{NAME}_FILE` pattern
+    pub const fn from_file(mut self, from_file: bool) -> Self {
+        self.from_file = from_file;
+        self
+    }
+
+    /// Mark the value as secret, keeping it and the parser's message out of parse errors
+    pub const fn secret(mut self, secret: bool) -> Self {
+        self.secret = secret;
+        self
+    }
+
+    /// Include a truncated snippet of the value in parse errors (ignored for secrets)
+    pub const fn show_value(mut self, show_value: bool) -> Self {
+        self.show_value = show_value;
+        self
+    }
+
+    /// Record where the loaded value comes from in `recorder`
+    pub const fn record(mut self, recorder: Option<&'a SourceRecorder>) -> Self {
+        self.recorder = recorder;
+        self
+    }
+
+    /// Base names in lookup order (primary name first, then aliases, then
+    /// deprecated names), paired with their deprecation entry if any
+    fn names(&self) -> impl Iterator<Item = (&'a str, Option<&'a Deprecated<'a>>)> {
+        std::iter::once(self.name)
+            .chain(self.aliases.iter().copied())
+            .map(|name| (name, None))
+            .chain(self.deprecated.iter().map(|d| (d.name, Some(d))))
+    }
+
+    /// Report that a value was read from a deprecated name
+    fn warn_deprecated(&self, deprecated: &Deprecated<'_>) {
+        deprecation::warn(&DeprecatedUsage {
+            name: deprecated.name,
+            replacement: self.name,
+            note: deprecated.note,
+        });
+    }
+
+    /// Every environment variable name consulted, in lookup order
+    fn candidates(&self) -> Vec<String> {
+        let from_file = self.from_file;
+        self.names()
+            .flat_map(|(name, _)| {
+                let file_name = from_file.then(|| format!("{}_FILE", name));
+                std::iter::once(name.to_string()).chain(file_name)
+            })
+            .collect()
+    }
+}
+
+/// Raw value read from the environment
+///
+/// Returned by [`get_env_value`] so that parse errors can report the name
+/// the value actually came from (which may be an alias).
+#[doc(hidden)]
+#[derive(Debug, Clone)]
+pub struct EnvValue<'a> {
+    /// Base name of the environment variable the value was read from
+    pub name: &'a str,
+    /// Raw string value
+    pub value: String,
+    /// Whether the value is a secret (copied from [`EnvVar::secret`])
+    pub secret: bool,
+    /// Whether parse errors show a snippet (copied from [`EnvVar::show_value`])
+    pub show_value: bool,
+}
+
+/// Maximum number of characters of a value shown in parse errors
+const SNIPPET_LEN: usize = 32;
+
+impl EnvValue<'_> {
+    /// Create a parse error for this value (used by macro-generated code)
+    ///
+    /// For secret values the parser's message, which may quote the input, is
+    /// replaced with a generic one; other values are attached as a truncated
+    /// snippet when `show_value` is enabled.
+    pub fn parse_error<T>(&self, message: impl std::fmt::Display) -> ServiceConfError {
+        let (message, value) = if self.secret {
+            (HIDDEN_MESSAGE.to_string(), None)
+        } else {
+            (
+                message.to_string(),
+                self.show_value.then(|| snippet(&self.value)),
+            )
+        };
+        ServiceConfError::Parse {
+            name: self.name.to_string(),
+            type_name: std::any::type_name::<T>().to_string(),
+            message,
+            value,
+        }
+    }
+}
+
+/// First [`SNIPPET_LEN`] characters of `value`, with `…` appended when truncated
+fn snippet(value: &str) -> String {
+    match value.char_indices().nth(SNIPPET_LEN) {
+        Some((end, _)) => format!("{}…", &value[..end]),
+        None => value.to_string(),
+    }
+}
+
+/// Sources of the values read while loading a configuration, keyed by primary name
+///
+/// Filled by [`get_env_value`] (and the `deserialize_*` functions) for variables
+/// built with [`EnvVar::record`], by the same lookup that produces the value.
+/// Used by macro-generated `from_env_with_provenance()`.
+#[doc(hidden)]
+#[derive(Debug, Default)]
+pub struct SourceRecorder {
+    sources: RefCell<Vec<(String, Source)>>,
+}
+
+impl SourceRecorder {
+    /// Create an empty recorder
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Source recorded for the variable with the given primary name, or `None`
+    /// if no value was read for it
+    pub fn get(&self, name: &str) -> Option<Source> {
+        self.sources
+            .borrow()
+            .iter()
+            .rev()
+            .find(|(recorded, _)| recorded == name)
+            .map(|(_, source)| source.clone())
+    }
+
+    fn record(&self, name: &str, source: Source) {
+        self.sources.borrow_mut().push((name.to_string(), source));
+    }
+}
+
+/// Collects errors while loading a configuration so they can be reported together
+///
+/// Used by macro-generated code unless the struct is marked `#[conf(fail_fast)]`.
+#[doc(hidden)]
+#[derive(Debug, Default)]
+pub struct ErrorCollector {
+    errors: Vec<ServiceConfError>,
+}
+
+impl ErrorCollector {
+    /// Create an empty collector
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record the error of a failed result, returning the value otherwise
+    pub fn take<T>(&mut self, result: Result<T, ServiceConfError>) -> Option<T> {
+        match result {
+            Ok(value) => Some(value),
+            Err(e) => {
+                self.errors.push(e);
+                None
+            }
+        }
+    }
+
+    /// Record the error of a failed check
+    pub fn check(&mut self, result: Result<(), ServiceConfError>) {
+        self.take(result);
+    }
+
+    /// Return the recorded errors, if any, and clear the collector
+    ///
+    /// A single error is returned as-is; several are wrapped in
+    /// [`ServiceConfError::Multiple`].
+    pub fn finish(&mut self) -> Result<(), ServiceConfError> {
+        let mut errors = std::mem::take(&mut self.errors);
+        match errors.len() {
+            0 => Ok(()),
+            1 => Err(errors.remove(0)),
+            _ => Err(ServiceConfError::Multiple(errors)),
+        }
+    }
+}
+
+/// Tells macro-generated code whether a field type is [`Secret`], however the
+/// type is spelled (`Secret<T>` after a `use`, a type alias, a full path)
+///
+/// For `Secret<T>`, `SecretProbe::<Secret<T>>::IS_SECRET` and `wrap` resolve to
+/// the inherent items below; for every other type they resolve to the defaults
+/// of [`NotSecret`], which must be in scope.
+#[doc(hidden)]
+pub struct SecretProbe<T>(PhantomData<T>);
+
+impl<T: Zeroize> SecretProbe<Secret<T>> {
+    /// The probed type is a `Secret`
+    pub const IS_SECRET: bool = true;
+
+    /// Wrap the inner value returned by a `deserializer` of a `Secret<T>` field
+    pub fn wrap<E>(result: Result<T, E>) -> Result<Secret<T>, E> {
+        result.map(Secret::new)
+    }
+}
+
+/// Fallback of [`SecretProbe`] for types other than [`Secret`]
+#[doc(hidden)]
+pub trait NotSecret<T> {
+    /// The probed type is not a `Secret`
+    const IS_SECRET: bool = false;
+
+    /// Pass the value returned by a `deserializer` through unchanged
+    fn wrap<E>(result: Result<T, E>) -> Result<T, E> {
+        result
+    }
+}
+
+impl<T> NotSecret<T> for SecretProbe<T> {}
+
+/// Placeholder printed by generated `Debug` impls instead of a secret value
+#[doc(hidden)]
+pub struct Masked;
+
+impl std::fmt::Debug for Masked {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("***")
+    }
+}
 
 /// Load a required value using `FromStr`
 ///
 /// Used by the derive macro for fields without default values.
 #[doc(hidden)]
-pub fn deserialize_required<T>(env_name: &str, from_file: bool) -> Result<T, ServiceConfError>
+pub fn deserialize_required<T>(var: &EnvVar<'_>) -> Result<T, ServiceConfError>
 where
     T: FromStr,
     T::Err: std::fmt::Display,
 {
-    let value = get_env_value(env_name, from_file)?;
-    value
-        .parse::<T>()
-        .map_err(|e| ServiceConfError::parse_error::<T>(env_name, e))
+    let env = get_env_value(var)?;
+    env.value.parse::<T>().map_err(|e| env.parse_error::<T>(e))
 }
 
 /// Load a value with a default using `FromStr`
 ///
 /// Used by the derive macro for fields with default values.
 #[doc(hidden)]
-pub fn deserialize_with_default<T>(
-    env_name: &str,
-    from_file: bool,
-    default: T,
+pub fn deserialize_with_default<T>(var: &EnvVar<'_>, default: T) -> Result<T, ServiceConfError>
+where
+    T: FromStr,
+    T::Err: std::fmt::Display,
+{
+    match get_env_value(var) {
+        Ok(env) => env.value.parse::<T>().map_err(|e| env.parse_error::<T>(e)),
+        Err(ServiceConfError::Missing { .. }) => Ok(default),
+        Err(e) => Err(e),
+    }
+}
+
+/// Load a value with a default written in environment variable syntax using `FromStr`
+///
+/// The default is only parsed when no environment variable is set.
+/// Used by the derive macro for fields with `default_str`.
+#[doc(hidden)]
+pub fn deserialize_with_default_str<T>(
+    var: &EnvVar<'_>,
+    default: &str,
 ) -> Result<T, ServiceConfError>
 where
     T: FromStr,
     T::Err: std::fmt::Display,
 {
-    match get_env_value(env_name, from_file) {
-        Ok(value) => value
+    match get_env_value(var) {
+        Ok(env) => env.value.parse::<T>().map_err(|e| env.parse_error::<T>(e)),
+        Err(ServiceConfError::Missing { .. }) => default
             .parse::<T>()
-            .map_err(|e| ServiceConfError::parse_error::<T>(env_name, e)),
-        Err(ServiceConfError::Missing { .. }) => Ok(default),
+            .map_err(|e| ServiceConfError::invalid_default::<T>(var.name, default, e)),
+        Err(e) => Err(e),
+    }
+}
+
+/// Check that a `default_str` value parses while the variable is set
+///
+/// When the variable is unset, loading parses the default itself and reports
+/// it; this check covers the other case, so an invalid default is caught in any
+/// environment. Used by the derive macro for `default_str` values it cannot
+/// check at compile time.
+#[doc(hidden)]
+pub fn check_default_str<T, E>(
+    var: &EnvVar<'_>,
+    default: &str,
+    parse: impl FnOnce(&str) -> Result<T, E>,
+) -> Result<(), ServiceConfError>
+where
+    E: std::fmt::Display,
+{
+    if !is_set(var) {
+        return Ok(());
+    }
+    parse(default)
+        .map(drop)
+        .map_err(|e| ServiceConfError::invalid_default::<T>(var.name, default, e))
+}
+
+/// Load a value using `FromStr`, computing the default only when unset
+///
+/// Used by the derive macro for fields with `default_fn` or `default_from`.
+#[doc(hidden)]
+pub fn deserialize_or_else<T>(
+    var: &EnvVar<'_>,
+    default: impl FnOnce() -> T,
+) -> Result<T, ServiceConfError>
+where
+    T: FromStr,
+    T::Err: std::fmt::Display,
+{
+    match get_env_value(var) {
+        Ok(env) => env.value.parse::<T>().map_err(|e| env.parse_error::<T>(e)),
+        Err(ServiceConfError::Missing { .. }) => Ok(default()),
         Err(e) => Err(e),
     }
 }
@@ -54,19 +410,17 @@ where
 /// Returns `None` if environment variable is not set, `Some(T)` if it is.
 /// Used by the derive macro for `Option<T>` fields.
 #[doc(hidden)]
-pub fn deserialize_optional<T>(
-    env_name: &str,
-    from_file: bool,
-) -> Result<Option<T>, ServiceConfError>
+pub fn deserialize_optional<T>(var: &EnvVar<'_>) -> Result<Option<T>, ServiceConfError>
 where
     T: FromStr,
     T::Err: std::fmt::Display,
 {
-    match get_env_value(env_name, from_file) {
-        Ok(value) => {
-            let parsed = value
+    match get_env_value(var) {
+        Ok(env) => {
+            let parsed = env
+                .value
                 .parse::<T>()
-                .map_err(|e| ServiceConfError::parse_error::<T>(env_name, e))?;
+                .map_err(|e| env.parse_error::<T>(e))?;
             Ok(Some(parsed))
         }
         Err(ServiceConfError::Missing { .. }) => Ok(None),
@@ -76,32 +430,151 @@ where
 
 /// Get environment variable value with optional file-based fallback
 ///
-/// Priority order:
-/// 1. Direct environment variable (`env_name`)
-/// 2. File from environment variable (`{env_name}_FILE`) if `from_file` is true
-/// 3. Error if neither is found
+/// Priority order, for the primary name, then each alias, then each deprecated name:
+/// 1. Direct environment variable (`{name}`)
+/// 2. File from environment variable (`{name}_FILE`) if `from_file` is true
+///
+/// Reading from a deprecated name emits a warning through [`crate::set_deprecation_hook`].
+/// Returns a `Missing` error listing every name that was tried if none is found.
 ///
 /// Used by macro-generated code.
 #[doc(hidden)]
-pub fn get_env_value(env_name: &str, from_file: bool) -> Result<String, ServiceConfError> {
-    if let Ok(value) = env::var(env_name) {
-        return Ok(value);
+pub fn get_env_value<'a>(var: &EnvVar<'a>) -> Result<EnvValue<'a>, ServiceConfError> {
+    lookup(var, true)
+}
+
+/// Get environment variable value like [`get_env_value`], without emitting
+/// deprecation warnings
+///
+/// Returns `None` if no name is set. Used by macro-generated constraint checks.
+#[doc(hidden)]
+pub fn peek_env_value<'a>(var: &EnvVar<'a>) -> Result<Option<EnvValue<'a>>, ServiceConfError> {
+    match lookup(var, false) {
+        Ok(env) => Ok(Some(env)),
+        Err(ServiceConfError::Missing { .. }) => Ok(None),
+        Err(e) => Err(e),
     }
+}
 
-    if from_file {
-        let file_var_name = format!("{}_FILE", env_name);
-        if let Ok(file_path) = env::var(&file_var_name) {
-            return fs::read_to_string(&file_path)
-                .map(|s| s.trim().to_string())
-                .map_err(|e| ServiceConfError::FileRead {
+/// Whether any name of the variable (or its `{name}_FILE` variant) is set,
+/// without reading files
+///
+/// Used by macro-generated constraint checks.
+#[doc(hidden)]
+pub fn is_set(var: &EnvVar<'_>) -> bool {
+    var.names().any(|(name, _)| {
+        env::var(name).is_ok() || (var.from_file && env::var(format!("{}_FILE", name)).is_ok())
+    })
+}
+
+/// First name of a variable that is set, in lookup order
+struct Located<'a> {
+    /// Base name that was found
+    name: &'a str,
+    /// Whether `name` is the primary name, an alias or a deprecated name
+    kind: NameKind,
+    /// Deprecation entry of `name`, if it is deprecated
+    deprecated: Option<&'a Deprecated<'a>>,
+    /// Value or file reference found under `name`
+    raw: Raw,
+}
+
+impl Located<'_> {
+    /// Where the value is read from
+    fn source(&self) -> Source {
+        match &self.raw {
+            Raw::Value(_) => Source::Env {
+                name: self.name.to_string(),
+                kind: self.kind,
+            },
+            Raw::File { name, path } => Source::File {
+                name: name.clone(),
+                path: path.clone(),
+                kind: self.kind,
+            },
+        }
+    }
+}
+
+/// Raw setting found for a name
+enum Raw {
+    /// Value of the variable itself
+    Value(String),
+    /// Path named by the `{name}_FILE` variable
+    File { name: String, path: String },
+}
+
+/// Resolve which name (or `_FILE` variant) of a variable is used
+///
+/// Priority order, for the primary name, then each alias, then each deprecated name:
+/// the variable itself, then its `{name}_FILE` variant if `from_file` is true.
+fn locate<'a>(var: &EnvVar<'a>) -> Option<Located<'a>> {
+    for (i, (name, deprecated)) in var.names().enumerate() {
+        let kind = match (i, deprecated) {
+            (_, Some(_)) => NameKind::Deprecated,
+            (0, None) => NameKind::Primary,
+            (_, None) => NameKind::Alias,
+        };
+        let located = |raw| Located {
+            name,
+            kind,
+            deprecated,
+            raw,
+        };
+
+        if let Ok(value) = env::var(name) {
+            return Some(located(Raw::Value(value)));
+        }
+
+        if var.from_file {
+            let file_var_name = format!("{}_FILE", name);
+            if let Ok(path) = env::var(&file_var_name) {
+                return Some(located(Raw::File {
                     name: file_var_name,
-                    path: file_path,
-                    source: e,
-                });
+                    path,
+                }));
+            }
+        }
+    }
+    None
+}
+
+/// Shared lookup for [`get_env_value`] and [`peek_env_value`]
+///
+/// When `loading`, deprecated names emit a warning and the source of the value
+/// is recorded in the variable's recorder.
+fn lookup<'a>(var: &EnvVar<'a>, loading: bool) -> Result<EnvValue<'a>, ServiceConfError> {
+    let Some(located) = locate(var) else {
+        return Err(ServiceConfError::missing_any(var.name, var.candidates()));
+    };
+    if loading {
+        if let Some(deprecated) = located.deprecated {
+            var.warn_deprecated(deprecated);
+        }
+        if let Some(recorder) = var.recorder {
+            recorder.record(var.name, located.source());
         }
     }
 
-    Err(ServiceConfError::missing(env_name))
+    let value = match located.raw {
+        Raw::Value(value) => value,
+        Raw::File { name, path } => match fs::read_to_string(&path) {
+            Ok(contents) => contents.trim().to_string(),
+            Err(e) => {
+                return Err(ServiceConfError::FileRead {
+                    name,
+                    path,
+                    source: e,
+                })
+            }
+        },
+    };
+    Ok(EnvValue {
+        name: located.name,
+        value,
+        secret: var.secret,
+        show_value: var.show_value,
+    })
 }
 
 #[cfg(test)]
@@ -114,7 +587,7 @@ mod tests {
     #[serial]
     fn test_deserialize_required_success() {
         env::set_var("TEST_VAR", "42");
-        let result: Result<i32, _> = deserialize_required("TEST_VAR", false);
+        let result: Result<i32, _> = deserialize_required(&EnvVar::new("TEST_VAR"));
         assert_eq!(result.unwrap(), 42);
         env::remove_var("TEST_VAR");
     }
@@ -123,7 +596,7 @@ mod tests {
     #[serial]
     fn test_deserialize_required_missing() {
         env::remove_var("MISSING_VAR");
-        let result: Result<String, _> = deserialize_required("MISSING_VAR", false);
+        let result: Result<String, _> = deserialize_required(&EnvVar::new("MISSING_VAR"));
         assert!(matches!(result, Err(ServiceConfError::Missing { .. })));
     }
 
@@ -131,7 +604,7 @@ mod tests {
     #[serial]
     fn test_deserialize_with_default_env_set() {
         env::set_var("TEST_DEFAULT", "100");
-        let result: u32 = deserialize_with_default("TEST_DEFAULT", false, 50).unwrap();
+        let result: u32 = deserialize_with_default(&EnvVar::new("TEST_DEFAULT"), 50).unwrap();
         assert_eq!(result, 100);
         env::remove_var("TEST_DEFAULT");
     }
@@ -140,10 +613,43 @@ mod tests {
     #[serial]
     fn test_deserialize_with_default_use_default() {
         env::remove_var("TEST_DEFAULT_MISSING");
-        let result: u32 = deserialize_with_default("TEST_DEFAULT_MISSING", false, 50).unwrap();
+        let result: u32 =
+            deserialize_with_default(&EnvVar::new("TEST_DEFAULT_MISSING"), 50).unwrap();
         assert_eq!(result, 50);
     }
 
+    #[test]
+    #[serial]
+    fn test_deserialize_with_default_str() {
+        env::remove_var("TEST_DEFAULT_STR");
+        let var = EnvVar::new("TEST_DEFAULT_STR");
+        let result: u32 = deserialize_with_default_str(&var, "50").unwrap();
+        assert_eq!(result, 50);
+
+        let result: Result<u32, _> = deserialize_with_default_str(&var, "fifty");
+        assert!(matches!(result, Err(ServiceConfError::Parse { .. })));
+
+        env::set_var("TEST_DEFAULT_STR", "100");
+        let result: u32 = deserialize_with_default_str(&var, "fifty").unwrap();
+        assert_eq!(result, 100);
+        env::remove_var("TEST_DEFAULT_STR");
+    }
+
+    #[test]
+    #[serial]
+    fn test_check_default_str() {
+        let var = EnvVar::new("TEST_CHECK_DEFAULT_STR");
+        let parse = |s: &str| s.parse::<u32>();
+        env::remove_var("TEST_CHECK_DEFAULT_STR");
+        assert!(check_default_str(&var, "fifty", parse).is_ok());
+
+        env::set_var("TEST_CHECK_DEFAULT_STR", "100");
+        assert!(check_default_str(&var, "50", parse).is_ok());
+        let err = check_default_str(&var, "fifty", parse).unwrap_err();
+        assert!(err.to_string().contains("invalid default value \"fifty\""));
+        env::remove_var("TEST_CHECK_DEFAULT_STR");
+    }
+
     #[test]
     #[serial]
     fn test_get_env_value_from_file() {
@@ -156,8 +662,8 @@ mod tests {
         env::set_var("TEST_FILE_VAR_FILE", temp_file.path());
         env::remove_var("TEST_FILE_VAR");
 
-        let result = get_env_value("TEST_FILE_VAR", true).unwrap();
-        assert_eq!(result, "secret_value");
+        let result = get_env_value(&EnvVar::new("TEST_FILE_VAR").from_file(true)).unwrap();
+        assert_eq!(result.value, "secret_value");
 
         env::remove_var("TEST_FILE_VAR_FILE");
     }
@@ -174,8 +680,8 @@ mod tests {
         env::set_var("TEST_PREFER", "direct_value");
         env::set_var("TEST_PREFER_FILE", temp_file.path());
 
-        let result = get_env_value("TEST_PREFER", true).unwrap();
-        assert_eq!(result, "direct_value");
+        let result = get_env_value(&EnvVar::new("TEST_PREFER").from_file(true)).unwrap();
+        assert_eq!(result.value, "direct_value");
 
         env::remove_var("TEST_PREFER");
         env::remove_var("TEST_PREFER_FILE");
@@ -187,8 +693,8 @@ mod tests {
         env::set_var("TEST_BOOL_TRUE", "true");
         env::set_var("TEST_BOOL_FALSE", "false");
 
-        let t: bool = deserialize_required("TEST_BOOL_TRUE", false).unwrap();
-        let f: bool = deserialize_required("TEST_BOOL_FALSE", false).unwrap();
+        let t: bool = deserialize_required(&EnvVar::new("TEST_BOOL_TRUE")).unwrap();
+        let f: bool = deserialize_required(&EnvVar::new("TEST_BOOL_FALSE")).unwrap();
 
         assert!(t);
         assert!(!f);
@@ -201,7 +707,7 @@ mod tests {
     #[serial]
     fn test_deserialize_string() {
         env::set_var("TEST_STRING", "hello world");
-        let result: String = deserialize_required("TEST_STRING", false).unwrap();
+        let result: String = deserialize_required(&EnvVar::new("TEST_STRING")).unwrap();
         assert_eq!(result, "hello world");
         env::remove_var("TEST_STRING");
     }
@@ -210,7 +716,7 @@ mod tests {
     #[serial]
     fn test_deserialize_url() {
         env::set_var("TEST_URL", "https://example.com/path?query=value");
-        let result: String = deserialize_required("TEST_URL", false).unwrap();
+        let result: String = deserialize_required(&EnvVar::new("TEST_URL")).unwrap();
         assert_eq!(result, "https://example.com/path?query=value");
         env::remove_var("TEST_URL");
     }
@@ -219,7 +725,7 @@ mod tests {
     #[serial]
     fn test_deserialize_optional_with_value() {
         env::set_var("TEST_OPT", "hello");
-        let result: Option<String> = deserialize_optional("TEST_OPT", false).unwrap();
+        let result: Option<String> = deserialize_optional(&EnvVar::new("TEST_OPT")).unwrap();
         assert_eq!(result, Some("hello".to_string()));
         env::remove_var("TEST_OPT");
     }
@@ -228,7 +734,8 @@ mod tests {
     #[serial]
     fn test_deserialize_optional_missing() {
         env::remove_var("TEST_OPT_MISSING");
-        let result: Option<String> = deserialize_optional("TEST_OPT_MISSING", false).unwrap();
+        let result: Option<String> =
+            deserialize_optional(&EnvVar::new("TEST_OPT_MISSING")).unwrap();
         assert_eq!(result, None);
     }
 
@@ -238,7 +745,7 @@ mod tests {
         env::remove_var("TEST_FILE_MISSING");
         env::set_var("TEST_FILE_MISSING_FILE", "/nonexistent/file/path");
 
-        let result = get_env_value("TEST_FILE_MISSING", true);
+        let result = get_env_value(&EnvVar::new("TEST_FILE_MISSING").from_file(true));
         assert!(matches!(result, Err(ServiceConfError::FileRead { .. })));
 
         env::remove_var("TEST_FILE_MISSING_FILE");
@@ -248,7 +755,7 @@ mod tests {
     #[serial]
     fn test_parse_error_contains_type_info() {
         env::set_var("TEST_PARSE_ERR", "not_a_number");
-        let result: Result<u32, _> = deserialize_required("TEST_PARSE_ERR", false);
+        let result: Result<u32, _> = deserialize_required(&EnvVar::new("TEST_PARSE_ERR"));
 
         match result {
             Err(ServiceConfError::Parse { type_name, .. }) => {
@@ -259,4 +766,206 @@ mod tests {
 
         env::remove_var("TEST_PARSE_ERR");
     }
+
+    #[test]
+    #[serial]
+    fn test_parse_error_redacts_secrets() {
+        env::set_var("TEST_SECRET_PORT", "hunter2");
+        let var = EnvVar::new("TEST_SECRET_PORT")
+            .secret(true)
+            .show_value(true);
+        let err = deserialize_required::<serde_json::Value>(&var).unwrap_err();
+        assert!(!err.to_string().contains("hunter2"));
+        assert!(matches!(
+            err,
+            ServiceConfError::Parse { ref message, value: None, .. } if message == HIDDEN_MESSAGE
+        ));
+        env::remove_var("TEST_SECRET_PORT");
+    }
+
+    #[test]
+    #[serial]
+    fn test_parse_error_shows_value_snippet() {
+        env::set_var("TEST_SNIPPET_PORT", "eighty");
+        let var = EnvVar::new("TEST_SNIPPET_PORT");
+        let err = deserialize_required::<u16>(&var).unwrap_err();
+        assert!(!err.to_string().contains("eighty"));
+
+        let err = deserialize_required::<u16>(&var.show_value(true)).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Failed to parse environment variable 'TEST_SNIPPET_PORT' as u16: \
+             invalid digit found in string (value: \"eighty\")"
+        );
+        env::remove_var("TEST_SNIPPET_PORT");
+    }
+
+    #[test]
+    #[serial]
+    fn test_recorded_source_follows_lookup_order() {
+        use std::io::Write;
+        use tempfile::NamedTempFile;
+
+        let mut temp_file = NamedTempFile::new().unwrap();
+        writeln!(temp_file, "from_file").unwrap();
+        let path = temp_file.path().to_str().unwrap().to_string();
+
+        let recorder = SourceRecorder::new();
+        let var = EnvVar::new("TEST_SOURCE")
+            .aliases(&["TEST_SOURCE_ALIAS"])
+            .from_file(true)
+            .record(Some(&recorder));
+        env::remove_var("TEST_SOURCE");
+        env::remove_var("TEST_SOURCE_FILE");
+        env::remove_var("TEST_SOURCE_ALIAS");
+        env::remove_var("TEST_SOURCE_ALIAS_FILE");
+        assert!(get_env_value(&var).is_err());
+        assert_eq!(recorder.get("TEST_SOURCE"), None);
+
+        env::set_var("TEST_SOURCE_ALIAS_FILE", &path);
+        assert_eq!(get_env_value(&var).unwrap().value, "from_file");
+        assert_eq!(
+            recorder.get("TEST_SOURCE"),
+            Some(Source::File {
+                name: "TEST_SOURCE_ALIAS_FILE".to_string(),
+                path,
+                kind: NameKind::Alias,
+            })
+        );
+
+        env::set_var("TEST_SOURCE", "value");
+        assert_eq!(get_env_value(&var).unwrap().value, "value");
+        assert_eq!(
+            recorder.get("TEST_SOURCE"),
+            Some(Source::Env {
+                name: "TEST_SOURCE".to_string(),
+                kind: NameKind::Primary,
+            })
+        );
+
+        // Peeking (constraint checks) does not record
+        let peeked = SourceRecorder::new();
+        peek_env_value(&var.record(Some(&peeked))).unwrap();
+        assert_eq!(peeked.get("TEST_SOURCE"), None);
+
+        env::remove_var("TEST_SOURCE");
+        env::remove_var("TEST_SOURCE_ALIAS_FILE");
+    }
+
+    #[test]
+    fn test_snippet() {
+        assert_eq!(snippet("short"), "short");
+        let long = "x".repeat(40);
+        assert_eq!(snippet(&long), format!("{}…", "x".repeat(32)));
+        assert_eq!(snippet(&"é".repeat(33)), format!("{}…", "é".repeat(32)));
+    }
+
+    #[test]
+    #[serial]
+    fn test_get_env_value_falls_back_to_alias() {
+        env::remove_var("TEST_PRIMARY");
+        env::set_var("TEST_ALIAS", "alias_value");
+
+        let var = EnvVar::new("TEST_PRIMARY").aliases(&["TEST_ALIAS"]);
+        let result = get_env_value(&var).unwrap();
+        assert_eq!(result.name, "TEST_ALIAS");
+        assert_eq!(result.value, "alias_value");
+
+        env::set_var("TEST_PRIMARY", "primary_value");
+        let result = get_env_value(&var).unwrap();
+        assert_eq!(result.name, "TEST_PRIMARY");
+        assert_eq!(result.value, "primary_value");
+
+        env::remove_var("TEST_PRIMARY");
+        env::remove_var("TEST_ALIAS");
+    }
+
+    #[test]
+    #[serial]
+    fn test_missing_lists_every_name_tried() {
+        env::remove_var("TEST_NEW");
+        env::remove_var("TEST_NEW_FILE");
+        env::remove_var("TEST_OLD");
+        env::remove_var("TEST_OLD_FILE");
+
+        let var = EnvVar::new("TEST_NEW")
+            .aliases(&["TEST_OLD"])
+            .from_file(true);
+        match get_env_value(&var) {
+            Err(ServiceConfError::Missing { name, tried }) => {
+                assert_eq!(name, "TEST_NEW");
+                assert_eq!(
+                    tried,
+                    vec!["TEST_NEW", "TEST_NEW_FILE", "TEST_OLD", "TEST_OLD_FILE"]
+                );
+            }
+            _ => panic!("Expected Missing error"),
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn test_is_set_and_peek() {
+        env::remove_var("TEST_PEEK");
+        env::remove_var("TEST_PEEK_FILE");
+
+        let var = EnvVar::new("TEST_PEEK").from_file(true);
+        assert!(!is_set(&var));
+        assert!(peek_env_value(&var).unwrap().is_none());
+
+        // `_FILE` presence counts without the file being read
+        env::set_var("TEST_PEEK_FILE", "/nonexistent/peek");
+        assert!(is_set(&var));
+        assert!(!is_set(&EnvVar::new("TEST_PEEK")));
+        env::remove_var("TEST_PEEK_FILE");
+
+        env::set_var("TEST_PEEK", "s3");
+        assert_eq!(peek_env_value(&var).unwrap().unwrap().value, "s3");
+        env::remove_var("TEST_PEEK");
+    }
+
+    #[test]
+    fn test_secret_probe() {
+        type Token = Secret<String>;
+
+        let probed = [
+            SecretProbe::<Secret<u32>>::IS_SECRET,
+            SecretProbe::<Token>::IS_SECRET,
+            SecretProbe::<u32>::IS_SECRET,
+            SecretProbe::<Option<Secret<u32>>>::IS_SECRET,
+        ];
+        assert_eq!(probed, [true, true, false, false]);
+
+        let wrapped: Result<Secret<u32>, ()> = SecretProbe::<Secret<u32>>::wrap(Ok(7));
+        assert_eq!(*wrapped.unwrap().expose(), 7);
+        let plain: Result<u32, ()> = SecretProbe::<u32>::wrap(Ok(7));
+        assert_eq!(plain, Ok(7));
+    }
+
+    #[test]
+    fn test_error_collector() {
+        let mut errors = ErrorCollector::new();
+        assert_eq!(errors.take(Ok::<_, ServiceConfError>(1)), Some(1));
+        assert!(errors.finish().is_ok());
+
+        errors.check(Err(ServiceConfError::missing("A")));
+        assert!(matches!(
+            errors.finish(),
+            Err(ServiceConfError::Missing { .. })
+        ));
+
+        errors.check(Err(ServiceConfError::missing("A")));
+        assert_eq!(
+            errors.take::<u16>(Err(ServiceConfError::missing("B"))),
+            None
+        );
+        let err = errors.finish().unwrap_err();
+        assert_eq!(err.errors().len(), 2);
+        assert_eq!(
+            err.to_string(),
+            "2 configuration errors:\n  \
+             - Environment variable 'A' is required but not set\n  \
+             - Environment variable 'B' is required but not set"
+        );
+    }
 }
@@ -0,0 +1,82 @@
+//! Warnings for deprecated environment variable names
+//!
+//! Fields annotated with `#[conf(deprecated_name = "OLD_NAME")]` still accept the
+//! old name, but every time a value is read from it a warning is emitted so that
+//! deployments relying on retired names can be found before the name is removed.
+//!
+//! Warnings are delivered to the hook installed with [`set_deprecation_hook`].
+//! Without a hook, they are forwarded to exactly one facade: `tracing` when that
+//! crate feature is enabled, otherwise `log` when that one is. With neither a
+//! hook nor a facade, warnings are discarded.
+
+use std::fmt;
+use std::sync::RwLock;
+
+type Hook = Box<dyn Fn(&DeprecatedUsage<'_>) + Send + Sync>;
+
+static HOOK: RwLock<Option<Hook>> = RwLock::new(None);
+
+/// A value was read from a deprecated environment variable name.
+///
+/// Passed to the hook installed with [`set_deprecation_hook`]. The `Display`
+/// implementation renders a human-readable warning message.
+#[derive(Debug, Clone, Copy)]
+pub struct DeprecatedUsage<'a> {
+    /// Deprecated environment variable name the value was read from
+    pub name: &'a str,
+    /// Current environment variable name that should be used instead
+    pub replacement: &'a str,
+    /// Note from `#[conf(deprecated_name = "...", note = "...")]`, if any
+    pub note: Option<&'a str>,
+}
+
+impl fmt::Display for DeprecatedUsage<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Environment variable '{}' is deprecated, use '{}' instead",
+            self.name, self.replacement
+        )?;
+        if let Some(note) = self.note {
+            write!(f, ": {}", note)?;
+        }
+        Ok(())
+    }
+}
+
+/// Install a process-wide hook that receives deprecated name usages.
+///
+/// Replaces any previously installed hook. The hook takes precedence over the
+/// `log`/`tracing` integrations.
+///
+/// ```
+/// serviceconf::set_deprecation_hook(|usage| {
+///     eprintln!("config warning: {}", usage);
+/// });
+/// ```
+pub fn set_deprecation_hook<F>(hook: F)
+where
+    F: Fn(&DeprecatedUsage<'_>) + Send + Sync + 'static,
+{
+    *HOOK.write().unwrap_or_else(|e| e.into_inner()) = Some(Box::new(hook));
+}
+
+/// Report usage of a deprecated name (used by [`crate::de`])
+pub(crate) fn warn(usage: &DeprecatedUsage<'_>) {
+    let hook = HOOK.read().unwrap_or_else(|e| e.into_inner());
+    if let Some(hook) = hook.as_ref() {
+        hook(usage);
+    } else {
+        // A single sink, so enabling both features does not report twice
+        #[cfg(feature = "tracing")]
+        tracing::warn!(
+            name = usage.name,
+            replacement = usage.replacement,
+            "{}",
+            usage
+        );
+
+        #[cfg(all(feature = "log", not(feature = "tracing")))]
+        log::warn!("{}", usage);
+    }
+}
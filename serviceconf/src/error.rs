@@ -1,21 +1,37 @@
 //! Error types for environment variable configuration
 
+/// Message used in place of parser and validator messages for secret fields,
+/// which may quote the value
+pub(crate) const HIDDEN_MESSAGE: &str = "invalid value (details hidden for secret field)";
+
 /// Errors that can occur when loading configuration from environment variables.
 ///
-/// This error type covers three main failure scenarios:
+/// This error type covers the following failure scenarios:
 /// - Missing required environment variables
 /// - File read failures when using the `{VAR}_FILE` pattern
 /// - Type parsing failures during deserialization
+/// - Validation failures for values that parsed but break a declared rule
+/// - Cross-field constraints (`requires`, `conflicts_with`, `required_if`) being violated
+/// - Struct-level validation failures for invariants spanning several fields
+///
+/// When several of these occur while loading, they are reported together as
+/// [`ServiceConfError::Multiple`].
+///
+/// Variants may be added in minor releases, so matches need a wildcard arm.
 #[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
 pub enum ServiceConfError {
     /// Required environment variable is not set.
     ///
     /// Occurs when a non-optional field's environment variable is not found
     /// and no default value is specified.
-    #[error("Environment variable '{name}' is required but not set")]
+    #[error("Environment variable '{name}' is required but not set{}", tried_suffix(.tried))]
     Missing {
         /// Name of the missing environment variable
         name: String,
+        /// Every environment variable name that was tried, in lookup order
+        /// (including `{VAR}_FILE` variants and aliases)
+        tried: Vec<String>,
     },
 
     /// Failed to read from a file specified by `{VAR}_FILE` environment variable.
@@ -37,7 +53,10 @@ pub enum ServiceConfError {
     ///
     /// Occurs when the string value cannot be converted to the field's type,
     /// either via `FromStr` or a custom deserializer function.
-    #[error("Failed to parse environment variable '{name}' as {type_name}: {message}")]
+    ///
+    /// For secret fields (`#[conf(secret)]`, `from_file` and `Secret<T>`), the
+    /// parser's message is replaced with a generic one, since it may quote the value.
+    #[error("Failed to parse environment variable '{name}' as {type_name}: {message}{}", value_suffix(.value))]
     Parse {
         /// Name of the environment variable being parsed
         name: String,
@@ -45,10 +64,71 @@ pub enum ServiceConfError {
         type_name: String,
         /// Error message from the parser (FromStr or custom deserializer)
         message: String,
+        /// Truncated snippet of the offending value, included for non-secret
+        /// fields of structs marked `#[conf(show_values)]`
+        value: Option<String>,
+    },
+
+    /// Parsed value failed a validation rule.
+    ///
+    /// Occurs when a field declares a rule such as `#[conf(range(min = 1024))]`
+    /// or `#[conf(non_empty)]` and the loaded value (or default) does not satisfy it.
+    #[error("Environment variable '{name}' failed {rule} validation: {message}")]
+    Validation {
+        /// Name of the environment variable being validated
+        name: String,
+        /// Name of the rule that failed (e.g., "range", "one_of")
+        rule: String,
+        /// Description of the violated constraint
+        message: String,
+    },
+
+    /// Cross-field constraint between two environment variables was violated.
+    ///
+    /// Occurs when a field declares `requires`, `conflicts_with` or `required_if`
+    /// and the presence (or value) of the variables does not satisfy it.
+    #[error("Environment variable '{name}' {}", constraint_message(.constraint, .other))]
+    Constraint {
+        /// Name of the environment variable declaring the constraint
+        name: String,
+        /// Name of the other environment variable involved
+        other: String,
+        /// Kind of constraint that was violated
+        constraint: Constraint,
+    },
+
+    /// Several of the errors above occurred while loading one configuration.
+    ///
+    /// Loading collects every problem before failing (unless the struct is marked
+    /// `#[conf(fail_fast)]`); when more than one is found they are reported together,
+    /// one per line: cross-field constraint violations first, then field errors in
+    /// loading order. Fields load in declaration order, except that a field whose
+    /// default is computed from other fields loads after them.
+    #[error("{} configuration errors:{}", .0.len(), error_lines(.0))]
+    Multiple(Vec<ServiceConfError>),
+
+    /// Fully loaded configuration failed a struct-level `validate` function.
+    ///
+    /// Occurs when `#[conf(validate = path)]` on the struct returns an error,
+    /// typically for invariants spanning several fields.
+    #[error("Invalid configuration for {type_name}: {message}")]
+    InvalidConfig {
+        /// Fully qualified name of the configuration struct
+        type_name: String,
+        /// Error message returned by the validator
+        message: String,
     },
 }
 
 impl ServiceConfError {
+    /// Individual errors: the errors inside `Multiple`, or this error alone
+    pub fn errors(&self) -> &[ServiceConfError] {
+        match self {
+            Self::Multiple(errors) => errors,
+            other => std::slice::from_ref(other),
+        }
+    }
+
     /// Create a parse error (used by macro-generated code)
     #[doc(hidden)]
     pub fn parse_error<T>(name: impl Into<String>, message: impl std::fmt::Display) -> Self {
@@ -56,12 +136,123 @@ impl ServiceConfError {
             name: name.into(),
             type_name: std::any::type_name::<T>().to_string(),
             message: message.to_string(),
+            value: None,
+        }
+    }
+
+    /// Create a parse error for an invalid `default_str` value (used by macro-generated code)
+    #[doc(hidden)]
+    pub fn invalid_default<T>(
+        name: impl Into<String>,
+        default: &str,
+        message: impl std::fmt::Display,
+    ) -> Self {
+        Self::parse_error::<T>(
+            name,
+            format!("invalid default value {:?}: {}", default, message),
+        )
+    }
+
+    /// Create a validation error (used by macro-generated code)
+    #[doc(hidden)]
+    pub fn validation(
+        name: impl Into<String>,
+        rule: impl Into<String>,
+        message: impl Into<String>,
+    ) -> Self {
+        Self::Validation {
+            name: name.into(),
+            rule: rule.into(),
+            message: message.into(),
+        }
+    }
+
+    /// Create a cross-field constraint error (used by macro-generated code)
+    #[doc(hidden)]
+    pub fn constraint(
+        name: impl Into<String>,
+        other: impl Into<String>,
+        constraint: Constraint,
+    ) -> Self {
+        Self::Constraint {
+            name: name.into(),
+            other: other.into(),
+            constraint,
+        }
+    }
+
+    /// Create a struct-level validation error (used by macro-generated code)
+    #[doc(hidden)]
+    pub fn invalid_config<T>(message: impl std::fmt::Display) -> Self {
+        Self::InvalidConfig {
+            type_name: std::any::type_name::<T>().to_string(),
+            message: message.to_string(),
         }
     }
 
     /// Create a missing environment variable error (used by macro-generated code)
     #[doc(hidden)]
     pub fn missing(name: impl Into<String>) -> Self {
-        Self::Missing { name: name.into() }
+        let name = name.into();
+        Self::Missing {
+            tried: vec![name.clone()],
+            name,
+        }
+    }
+
+    /// Create a missing environment variable error listing every name tried
+    /// (used by macro-generated code)
+    #[doc(hidden)]
+    pub fn missing_any(name: impl Into<String>, tried: Vec<String>) -> Self {
+        Self::Missing {
+            name: name.into(),
+            tried,
+        }
+    }
+}
+
+/// Kind of cross-field constraint reported by [`ServiceConfError::Constraint`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Constraint {
+    /// The variable is set but the variable it requires is not
+    Requires,
+    /// The variable is set together with a variable it conflicts with
+    ConflictsWith,
+    /// The variable is not set although the other variable has the given value
+    RequiredIf {
+        /// Raw value of the other variable that makes this one required
+        value: String,
+    },
+}
+
+/// Describe a violated constraint relative to the declaring variable
+fn constraint_message(constraint: &Constraint, other: &str) -> String {
+    match constraint {
+        Constraint::Requires => format!("requires '{}' to be set", other),
+        Constraint::ConflictsWith => format!("cannot be set together with '{}'", other),
+        Constraint::RequiredIf { value } => {
+            format!("is required when '{}' is '{}'", other, value)
+        }
+    }
+}
+
+/// Format each error on its own indented line
+fn error_lines(errors: &[ServiceConfError]) -> String {
+    errors.iter().map(|e| format!("\n  - {}", e)).collect()
+}
+
+/// Format the value snippet of a parse error, omitted when there is none
+fn value_suffix(value: &Option<String>) -> String {
+    match value {
+        Some(value) => format!(" (value: {:?})", value),
+        None => String::new(),
+    }
+}
+
+/// Format the list of names tried, omitted when only the primary name was consulted
+fn tried_suffix(tried: &[String]) -> String {
+    if tried.len() <= 1 {
+        return String::new();
     }
+    format!(" (tried: {})", tried.join(", "))
 }
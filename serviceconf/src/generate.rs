@@ -0,0 +1,1139 @@
+//! Generated files derived from a configuration's [`EnvVarSpec`]s.
+//!
+//! [`ServiceConfSpec`](crate::ServiceConfSpec) exposes these as methods of the
+//! derived type (e.g. `Config::env_example()`), so
+//! files that describe the configuration can be regenerated from code and
+//! diffed in CI instead of being maintained by hand.
+
+use crate::{EnvVarSpec, Validation};
+use serde_json::{json, Map, Value};
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Error returned by [`check_file`]
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum CheckError {
+    /// The file's contents differ from the current rendering
+    #[error("{} is out of date", .path.display())]
+    OutOfDate {
+        /// Path of the checked file
+        path: PathBuf,
+    },
+
+    /// The file could not be read (including when it does not exist)
+    #[error("Failed to read {}: {source}", .path.display())]
+    Read {
+        /// Path of the checked file
+        path: PathBuf,
+        /// Underlying I/O error
+        source: io::Error,
+    },
+}
+
+/// Check that a checked-in generated file matches its current rendering
+///
+/// For CI, from a test or a small binary of the application, so files such as
+/// `.env.example` cannot drift from the configuration struct.
+/// [`ServiceConfSpec::check_env_example`](crate::ServiceConfSpec::check_env_example)
+/// does this for [`env_example`].
+///
+/// ```no_run
+/// use serviceconf::{generate, ServiceConf, ServiceConfSpec};
+///
+/// #[derive(ServiceConf)]
+/// struct Config {
+///     pub database_url: String,
+/// }
+///
+/// generate::check_file("docs/config.md", &Config::markdown_table()).unwrap();
+/// ```
+pub fn check_file(path: impl AsRef<Path>, expected: &str) -> Result<(), CheckError> {
+    let path = path.as_ref();
+    let actual = std::fs::read_to_string(path).map_err(|source| CheckError::Read {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    if actual != expected {
+        return Err(CheckError::OutOfDate {
+            path: path.to_path_buf(),
+        });
+    }
+    Ok(())
+}
+
+/// Render an example env file listing every variable
+///
+/// Each variable is preceded by its doc comment as `#` comments. Required
+/// variables are marked `# required` and left empty; variables with a default
+/// are commented out with their default in environment variable syntax
+/// ([`EnvVarSpec::env_default_value`]), and optional ones are commented out empty.
+/// Defaults computed by Rust expressions have no such form unless the field
+/// uses `with = module` (whose `to_env_string` renders them): they are commented
+/// out empty after a `# default: <expression>` note. `from_file` fields note
+/// their `_FILE` variant.
+///
+/// ```
+/// use serviceconf::{ServiceConf, ServiceConfSpec};
+///
+/// #[derive(ServiceConf)]
+/// struct Config {
+///     /// Connection string for the primary database
+///     #[conf(from_file)]
+///     pub database_url: String,
+///
+///     #[conf(default = 8080)]
+///     pub port: u16,
+/// }
+///
+/// let example = Config::env_example();
+/// assert!(example.starts_with("# Connection string for the primary database\n"));
+/// assert!(example.contains("# required\n"));
+/// assert!(example.contains("DATABASE_URL_FILE"));
+/// assert!(example.ends_with("\n# PORT=8080\n"));
+/// ```
+///
+/// renders
+///
+/// ```text
+/// # Connection string for the primary database
+/// # required
+/// # Or set DATABASE_URL_FILE to the path of a file containing the value
+/// DATABASE_URL=
+///
+/// # PORT=8080
+/// ```
+pub fn env_example(vars: &[EnvVarSpec]) -> String {
+    let mut out = String::new();
+    for (i, var) in vars.iter().enumerate() {
+        if i > 0 {
+            out.push('\n');
+        }
+        push_doc(&mut out, "", var);
+        if var.from_file {
+            out.push_str(&format!(
+                "# Or set {}_FILE to the path of a file containing the value\n",
+                var.name
+            ));
+        }
+        match (var.required, var.env_default_value(), var.default) {
+            (true, _, _) => out.push_str(&format!("{}=\n", var.name)),
+            (false, Some(default), _) => out.push_str(&format!("# {}={}\n", var.name, default)),
+            (false, None, Some(expr)) => {
+                out.push_str(&format!("# default: {}\n", expr));
+                out.push_str(&format!("# {}=\n", var.name));
+            }
+            (false, None, None) => out.push_str(&format!("# {}=\n", var.name)),
+        }
+    }
+    out
+}
+
+/// Render a Markdown reference table of every variable
+///
+/// Columns are the variable name, type, default, whether it is required,
+/// whether the `_FILE` variant is read, and the doc comment. Variables without a
+/// `group` come first, followed by one `### Group` heading and table per group
+/// in order of first appearance.
+///
+/// ```
+/// use serviceconf::{ServiceConf, ServiceConfSpec};
+///
+/// #[derive(ServiceConf)]
+/// struct Config {
+///     /// Port the HTTP server listens on
+///     #[conf(default = 8080)]
+///     pub port: u16,
+///
+///     #[conf(from_file, group = "Database")]
+///     pub database_url: String,
+/// }
+///
+/// let table = Config::markdown_table();
+/// assert!(table.contains("| `PORT` | `u16` | `8080` | no | no | Port the HTTP server listens on |"));
+/// assert!(table.contains("### Database"));
+/// ```
+pub fn markdown_table(vars: &[EnvVarSpec]) -> String {
+    let mut out = String::new();
+    for (i, (group, vars)) in groups(vars).into_iter().enumerate() {
+        if i > 0 {
+            out.push('\n');
+        }
+        if let Some(group) = group {
+            out.push_str(&format!("### {}\n\n", group));
+        }
+        out.push_str("| Name | Type | Default | Required | `_FILE` | Description |\n");
+        out.push_str("| ---- | ---- | ------- | -------- | ------- | ----------- |\n");
+        for var in vars {
+            let cells = [
+                format!("`{}`", var.name),
+                format!("`{}`", var.type_name),
+                var.default
+                    .map(|default| format!("`{}`", default))
+                    .unwrap_or_default(),
+                yes_no(var.required).to_string(),
+                yes_no(var.from_file).to_string(),
+                description(var.doc, "<br>"),
+            ];
+            let cells: Vec<_> = cells.iter().map(|cell| cell.replace('|', "\\|")).collect();
+            out.push_str(&format!("| {} |\n", cells.join(" | ")));
+        }
+    }
+    out
+}
+
+/// Render an HTML reference table of every variable
+///
+/// Same columns and grouping as [`markdown_table`], with `<h3>` group headings.
+/// All text is HTML-escaped.
+pub fn html_table(vars: &[EnvVarSpec]) -> String {
+    let mut out = String::new();
+    for (group, vars) in groups(vars) {
+        if let Some(group) = group {
+            out.push_str(&format!("<h3>{}</h3>\n", escape_html(group)));
+        }
+        out.push_str("<table>\n<thead>\n<tr>");
+        for header in [
+            "Name",
+            "Type",
+            "Default",
+            "Required",
+            "<code>_FILE</code>",
+            "Description",
+        ] {
+            out.push_str(&format!("<th>{}</th>", header));
+        }
+        out.push_str("</tr>\n</thead>\n<tbody>\n");
+        for var in vars {
+            let code = |text: &str| format!("<code>{}</code>", escape_html(text));
+            let cells = [
+                code(var.name),
+                code(var.type_name),
+                var.default.map(code).unwrap_or_default(),
+                yes_no(var.required).to_string(),
+                yes_no(var.from_file).to_string(),
+                escape_html(&description(var.doc, "\n")).replace('\n', "<br>"),
+            ];
+            out.push_str("<tr>");
+            for cell in cells {
+                out.push_str(&format!("<td>{}</td>", cell));
+            }
+            out.push_str("</tr>\n");
+        }
+        out.push_str("</tbody>\n</table>\n");
+    }
+    out
+}
+
+/// Render a JSON Schema (draft 2020-12) for a deployment's environment
+///
+/// The schema describes an object of string values with one property per
+/// variable name, including aliases, deprecated names (marked `deprecated`) and
+/// `_FILE` variants. Each value is constrained as far as the raw string allows:
+///
+/// - integer, float, `bool` and `char` fields parsed with `FromStr` get a
+///   `pattern` or `enum` matching the accepted syntax
+/// - `one_of` becomes an `enum` when all values are literals
+/// - `regex` becomes a `pattern`, and `non_empty`, `min_len` and `max_len`
+///   become `minLength`/`maxLength` on `String` fields
+///
+/// Required variables are listed in `required`, or as an `anyOf` over their
+/// names when they can be set under more than one. Fields with a custom
+/// deserializer only get a description, and `range` and `validate` rules are
+/// left to the application. Variables not described by the schema are allowed.
+///
+/// ```
+/// use serviceconf::{ServiceConf, ServiceConfSpec};
+///
+/// #[derive(ServiceConf)]
+/// struct Config {
+///     #[conf(default = 8080)]
+///     pub port: u16,
+///
+///     #[conf(one_of = ["debug", "info"])]
+///     pub log_level: String,
+/// }
+///
+/// let schema: serde_json::Value = serde_json::from_str(&Config::json_schema()).unwrap();
+/// assert_eq!(schema["properties"]["PORT"]["pattern"], r"^\+?[0-9]+$");
+/// assert_eq!(schema["properties"]["LOG_LEVEL"]["enum"][1], "info");
+/// assert_eq!(schema["required"][0], "LOG_LEVEL");
+/// ```
+pub fn json_schema(vars: &[EnvVarSpec]) -> String {
+    let mut properties = Map::new();
+    let mut required = Vec::new();
+    let mut any_of = Vec::new();
+
+    for var in vars {
+        let value = value_schema(var);
+        let mut candidates = Vec::new();
+        for name in var.names() {
+            let deprecated = var.deprecated_names.contains(&name);
+            let mut property = value.clone();
+            if deprecated {
+                property.insert("deprecated".to_string(), Value::Bool(true));
+            }
+            properties.insert(name.to_string(), Value::Object(property));
+            candidates.push(name.to_string());
+
+            if var.from_file {
+                let file_name = format!("{}_FILE", name);
+                let mut property = Map::new();
+                property.insert("type".to_string(), json!("string"));
+                property.insert(
+                    "description".to_string(),
+                    json!(format!("Path of a file containing the value of {}", name)),
+                );
+                if deprecated {
+                    property.insert("deprecated".to_string(), Value::Bool(true));
+                }
+                properties.insert(file_name.clone(), Value::Object(property));
+                candidates.push(file_name);
+            }
+        }
+
+        if var.required {
+            if let [name] = candidates.as_slice() {
+                required.push(json!(name));
+            } else {
+                let alternatives: Vec<_> = candidates
+                    .iter()
+                    .map(|name| json!({ "required": [name] }))
+                    .collect();
+                any_of.push(json!({ "anyOf": alternatives }));
+            }
+        }
+    }
+
+    let mut schema = Map::new();
+    schema.insert(
+        "$schema".to_string(),
+        json!("https://json-schema.org/draft/2020-12/schema"),
+    );
+    schema.insert("type".to_string(), json!("object"));
+    schema.insert("properties".to_string(), Value::Object(properties));
+    if !required.is_empty() {
+        schema.insert("required".to_string(), Value::Array(required));
+    }
+    if !any_of.is_empty() {
+        schema.insert("allOf".to_string(), Value::Array(any_of));
+    }
+
+    let mut out = serde_json::to_string_pretty(&Value::Object(schema))
+        .expect("a JSON value always serializes");
+    out.push('\n');
+    out
+}
+
+/// `FromStr` syntax of unsigned integers.
+const UNSIGNED_PATTERN: &str = r"^\+?[0-9]+$";
+
+/// `FromStr` syntax of signed integers.
+const SIGNED_PATTERN: &str = r"^[+-]?[0-9]+$";
+
+/// `FromStr` syntax of floats, including `inf`, `infinity` and `NaN` in any case.
+const FLOAT_PATTERN: &str = r"^[+-]?([0-9]+\.?[0-9]*([eE][+-]?[0-9]+)?|\.[0-9]+([eE][+-]?[0-9]+)?|[iI][nN][fF]([iI][nN][iI][tT][yY])?|[nN][aA][nN])$";
+
+/// Schema of the string value of a variable.
+fn value_schema(var: &EnvVarSpec) -> Map<String, Value> {
+    let mut schema = Map::new();
+    schema.insert("type".to_string(), json!("string"));
+    if !var.doc.is_empty() {
+        schema.insert("description".to_string(), json!(var.doc));
+    }
+    // Custom parsers accept whatever syntax they define
+    if var.deserializer.is_some() {
+        return schema;
+    }
+
+    let value_type = value_type(var.type_name);
+    match value_type {
+        "u8" | "u16" | "u32" | "u64" | "u128" | "usize" => {
+            schema.insert("pattern".to_string(), json!(UNSIGNED_PATTERN));
+        }
+        "i8" | "i16" | "i32" | "i64" | "i128" | "isize" => {
+            schema.insert("pattern".to_string(), json!(SIGNED_PATTERN));
+        }
+        "f32" | "f64" => {
+            schema.insert("pattern".to_string(), json!(FLOAT_PATTERN));
+        }
+        "bool" => {
+            schema.insert("enum".to_string(), json!(["true", "false"]));
+        }
+        "char" => {
+            schema.insert("minLength".to_string(), json!(1));
+            schema.insert("maxLength".to_string(), json!(1));
+        }
+        _ => {}
+    }
+
+    // Lengths count characters only for strings (elements for collections)
+    let is_string = value_type == "String";
+    let mut min_length = None;
+    for validation in var.validations {
+        match validation {
+            Validation::OneOf(values) => {
+                if let Some(values) = values.iter().map(|value| literal(value)).collect() {
+                    schema.remove("pattern");
+                    schema.insert("enum".to_string(), Value::Array(values));
+                }
+            }
+            Validation::Regex(pattern) => {
+                schema.insert("pattern".to_string(), json!(pattern));
+            }
+            Validation::NonEmpty if is_string => {
+                min_length = min_length.max(Some(1));
+            }
+            Validation::MinLen(len) if is_string => {
+                min_length = min_length.max(Some(*len));
+            }
+            Validation::MaxLen(len) if is_string => {
+                schema.insert("maxLength".to_string(), json!(len));
+            }
+            _ => {}
+        }
+    }
+    if let Some(len) = min_length {
+        schema.insert("minLength".to_string(), json!(len));
+    }
+    schema
+}
+
+/// The parsed type of a field as a bare name: `Option<Secret<u16>>` gives `u16`.
+fn value_type(type_name: &str) -> &str {
+    let mut ty = type_name.trim();
+    while let Some((outer, rest)) = ty.split_once('<') {
+        let outer = outer.rsplit("::").next().unwrap_or(outer);
+        match (outer, rest.strip_suffix('>')) {
+            ("Option" | "Secret", Some(inner)) => ty = inner.trim(),
+            _ => return ty,
+        }
+    }
+    ty.rsplit("::").next().unwrap_or(ty)
+}
+
+/// The environment variable form of a literal written in a `one_of` list, or
+/// `None` for anything else (paths, constants, escaped strings).
+fn literal(value: &str) -> Option<Value> {
+    if let Some(string) = value.strip_prefix('"').and_then(|v| v.strip_suffix('"')) {
+        return (!string.contains('\\')).then(|| json!(string));
+    }
+    let digits = value.strip_prefix('-').unwrap_or(value);
+    let is_number = digits.starts_with(|c: char| c.is_ascii_digit())
+        && digits
+            .chars()
+            .all(|c| c.is_ascii_digit() || c == '.' || c == '_');
+    if is_number || value == "true" || value == "false" {
+        return Some(json!(value.replace('_', "")));
+    }
+    None
+}
+
+/// Directory the Kubernetes Secret is mounted at in [`kubernetes_env`].
+const KUBERNETES_SECRETS_DIR: &str = "/etc/secrets";
+
+/// Directory Docker mounts Compose secrets at.
+const COMPOSE_SECRETS_DIR: &str = "/run/secrets";
+
+/// Render a Kubernetes container `env:` block for every variable
+///
+/// Secret fields read from `secret_name` with `valueFrom.secretKeyRef`, and
+/// `from_file` fields set their `_FILE` variable to the key's path under
+/// `/etc/secrets`, followed by a commented `volumeMounts`/`volumes` hint that
+/// mounts the Secret there. Keys are the field names in kebab-case. Required
+/// variables are active entries; variables with a default or `Option<T>` are
+/// commented out (showing the default in environment variable syntax), and doc
+/// comments are kept as comments. Defaults computed by Rust expressions (without
+/// `with = module` to render them) are commented out empty after a
+/// `# default: <expression>` note. When no entry is active the block is
+/// `env: []`, followed by the commented entries.
+///
+/// # Panics
+///
+/// Panics if `secret_name` is not a valid Kubernetes object name (a DNS-1123
+/// subdomain: at most 253 lowercase alphanumeric characters, `-` or `.`,
+/// starting and ending with an alphanumeric character).
+///
+/// ```
+/// use serviceconf::{ServiceConf, ServiceConfSpec};
+///
+/// #[derive(ServiceConf)]
+/// struct Config {
+///     #[conf(from_file)]
+///     pub api_key: String,
+/// }
+///
+/// let env = Config::kubernetes_env("app-secrets");
+/// assert!(env.contains("  - name: API_KEY_FILE\n    value: /etc/secrets/api-key\n"));
+/// assert!(env.contains("secretName: app-secrets"));
+/// ```
+pub fn kubernetes_env(vars: &[EnvVarSpec], secret_name: &str) -> String {
+    assert!(
+        is_dns_subdomain(secret_name),
+        "invalid Kubernetes Secret name {:?}: expected a DNS-1123 subdomain \
+         (lowercase alphanumeric characters, '-' or '.')",
+        secret_name
+    );
+
+    let mut body = String::new();
+    let mut file_keys = Vec::new();
+    for var in vars {
+        push_doc(&mut body, "  ", var);
+        push_default_note(&mut body, "  ", var);
+        let key = secret_key(var);
+        let entry = if var.from_file {
+            let entry = vec![
+                format!("- name: {}_FILE", var.name),
+                format!("  value: {}/{}", KUBERNETES_SECRETS_DIR, key),
+            ];
+            file_keys.push(key);
+            entry
+        } else if var.secret {
+            vec![
+                format!("- name: {}", var.name),
+                "  valueFrom:".to_string(),
+                "    secretKeyRef:".to_string(),
+                format!("      name: {}", secret_name),
+                format!("      key: {}", key),
+            ]
+        } else {
+            vec![
+                format!("- name: {}", var.name),
+                format!("  value: {}", yaml_value(var)),
+            ]
+        };
+        push_entry(&mut body, "  ", &entry, var.required);
+    }
+
+    let mut out = section("env", "[]", vars);
+    out.push_str(&body);
+    if !file_keys.is_empty() {
+        out.push_str(&format!(
+            "# Mount the {} Secret for the _FILE variables above:\n",
+            secret_name
+        ));
+        let mut hint = vec![
+            "volumeMounts:".to_string(),
+            "  - name: secrets".to_string(),
+            format!("    mountPath: {}", KUBERNETES_SECRETS_DIR),
+            "    readOnly: true".to_string(),
+            "volumes:".to_string(),
+            "  - name: secrets".to_string(),
+            "    secret:".to_string(),
+            format!("      secretName: {}", secret_name),
+            "      items:".to_string(),
+        ];
+        for key in file_keys {
+            hint.push(format!("        - key: {}", key));
+            hint.push(format!("          path: {}", key));
+        }
+        push_entry(&mut out, "", &hint, false);
+    }
+    out
+}
+
+/// Render a docker-compose service's `environment:` and `secrets:` sections
+///
+/// `from_file` fields set their `_FILE` variable to `/run/secrets/<key>` and
+/// list the secret under `secrets:`, followed by a commented hint for the
+/// top-level secret definitions. Keys are the field names in kebab-case.
+/// Compose only provides secrets as files, so other secret fields are
+/// interpolated from the shell or `.env` file running Compose
+/// (`${NAME:?NAME is required}` when required) instead of being written out,
+/// with a comment suggesting `from_file`.
+/// Required variables are active entries; variables with a default or
+/// `Option<T>` are commented out (showing the default in environment variable
+/// syntax), and doc comments are kept as comments. Defaults computed by Rust
+/// expressions (without `with = module` to render them) are commented out empty
+/// after a `# default: <expression>` note. A section with no active entry is
+/// written as `{}` or `[]`, followed by the commented entries.
+///
+/// ```
+/// use serviceconf::{ServiceConf, ServiceConfSpec};
+///
+/// #[derive(ServiceConf)]
+/// struct Config {
+///     pub database_url: String,
+///
+///     #[conf(from_file)]
+///     pub api_key: String,
+/// }
+///
+/// let compose = Config::compose_env();
+/// assert!(compose.contains("  DATABASE_URL: \"\"\n"));
+/// assert!(compose.contains("  API_KEY_FILE: /run/secrets/api-key\n"));
+/// assert!(compose.contains("secrets:\n  - api-key\n"));
+/// ```
+pub fn compose_env(vars: &[EnvVarSpec]) -> String {
+    let mut out = section("environment", "{}", vars);
+    let mut file_vars = Vec::new();
+    for var in vars {
+        push_doc(&mut out, "  ", var);
+        push_default_note(&mut out, "  ", var);
+        let entry = if var.from_file {
+            file_vars.push(var);
+            format!(
+                "{}_FILE: {}/{}",
+                var.name,
+                COMPOSE_SECRETS_DIR,
+                secret_key(var)
+            )
+        } else if var.secret {
+            out.push_str(
+                "  # Secret: interpolated when Compose runs; use from_file to mount it as a Compose secret\n",
+            );
+            let value = if var.required {
+                format!("${{{0}:?{0} is required}}", var.name)
+            } else {
+                format!("${{{}}}", var.name)
+            };
+            format!("{}: {}", var.name, json!(value))
+        } else {
+            format!("{}: {}", var.name, yaml_value(var))
+        };
+        push_entry(&mut out, "  ", &[entry], var.required);
+    }
+
+    if !file_vars.is_empty() {
+        out.push_str(&section("secrets", "[]", file_vars.iter().copied()));
+        for var in &file_vars {
+            push_entry(
+                &mut out,
+                "  ",
+                &[format!("- {}", secret_key(var))],
+                var.required,
+            );
+        }
+        out.push_str("# Top-level definitions of the secrets above:\n");
+        let mut hint = vec!["secrets:".to_string()];
+        for var in &file_vars {
+            let key = secret_key(var);
+            hint.push(format!("  {}:", key));
+            hint.push(format!("    file: ./secrets/{}", key));
+        }
+        push_entry(&mut out, "", &hint, false);
+    }
+    out
+}
+
+/// Write a variable's doc comment and `required` marker as YAML comments.
+fn push_doc(out: &mut String, indent: &str, var: &EnvVarSpec) {
+    for line in var.doc.lines() {
+        if line.is_empty() {
+            out.push_str(&format!("{}#\n", indent));
+        } else {
+            out.push_str(&format!("{}# {}\n", indent, line));
+        }
+    }
+    if var.required {
+        out.push_str(&format!("{}# required\n", indent));
+    }
+}
+
+/// Write the lines of a YAML entry, commented out unless `active`.
+fn push_entry(out: &mut String, indent: &str, lines: &[String], active: bool) {
+    let comment = if active { "" } else { "# " };
+    for line in lines {
+        out.push_str(&format!("{}{}{}\n", indent, comment, line));
+    }
+}
+
+/// Name of the secret holding a field's value: the field name in kebab-case.
+fn secret_key(var: &EnvVarSpec) -> String {
+    var.field.to_ascii_lowercase().replace('_', "-")
+}
+
+/// A variable's value as a quoted YAML scalar: its default in environment
+/// variable syntax, or empty.
+fn yaml_value(var: &EnvVarSpec) -> String {
+    // JSON strings are valid YAML double-quoted scalars
+    json!(var.env_default_value().unwrap_or_default()).to_string()
+}
+
+/// Write the `# default: <expression>` note of a variable whose default is
+/// computed by a Rust expression with no environment variable form.
+fn push_default_note(out: &mut String, indent: &str, var: &EnvVarSpec) {
+    if let (Some(expr), None) = (var.default, var.env_default_value()) {
+        out.push_str(&format!("{}# default: {}\n", indent, expr));
+    }
+}
+
+/// The `key:` line opening a YAML section of `vars`, or `key: <empty>` (`[]` or
+/// `{}`) when every entry is commented out, so the section parses as empty
+/// rather than `null`.
+fn section<'a>(key: &str, empty: &str, vars: impl IntoIterator<Item = &'a EnvVarSpec>) -> String {
+    if vars.into_iter().any(|var| var.required) {
+        format!("{}:\n", key)
+    } else {
+        format!("{}: {}\n", key, empty)
+    }
+}
+
+/// Whether `name` is a DNS-1123 subdomain, the syntax of Kubernetes object names.
+fn is_dns_subdomain(name: &str) -> bool {
+    name.len() <= 253
+        && name.split('.').all(|label| {
+            !label.is_empty()
+                && label
+                    .bytes()
+                    .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-')
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+        })
+}
+
+/// Variables split by `group`: ungrouped ones first, then each group in order
+/// of first appearance. Empty sections are omitted.
+fn groups(vars: &[EnvVarSpec]) -> Vec<(Option<&'static str>, Vec<&EnvVarSpec>)> {
+    let mut groups: Vec<(Option<&'static str>, Vec<&EnvVarSpec>)> = vec![(None, Vec::new())];
+    for var in vars {
+        match groups.iter_mut().find(|(group, _)| *group == var.group) {
+            Some((_, members)) => members.push(var),
+            None => groups.push((var.group, vec![var])),
+        }
+    }
+    groups.retain(|(_, members)| !members.is_empty());
+    groups
+}
+
+/// A doc comment on a single line: lines within a paragraph are joined with
+/// spaces and paragraphs with `separator`.
+fn description(doc: &str, separator: &str) -> String {
+    doc.split("\n\n")
+        .map(|paragraph| {
+            paragraph
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .filter(|paragraph| !paragraph.is_empty())
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+fn yes_no(value: bool) -> &'static str {
+    if value {
+        "yes"
+    } else {
+        "no"
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec(name: &'static str) -> EnvVarSpec {
+        EnvVarSpec {
+            field: "",
+            name,
+            aliases: &[],
+            deprecated_names: &[],
+            type_name: "String",
+            required: false,
+            default: None,
+            env_default: None,
+            env_default_fn: None,
+            from_file: false,
+            validations: &[],
+            deserializer: None,
+            secret: false,
+            doc: "",
+            group: None,
+        }
+    }
+
+    #[test]
+    fn test_env_example() {
+        let vars = [
+            EnvVarSpec {
+                required: true,
+                from_file: true,
+                doc: "API key for the upstream service\n\nMount it as a file in production.",
+                ..spec("API_KEY")
+            },
+            EnvVarSpec {
+                default: Some("info"),
+                env_default: Some("info"),
+                ..spec("LOG_LEVEL")
+            },
+            EnvVarSpec {
+                default: Some("Duration::from_secs(60)"),
+                ..spec("TIMEOUT")
+            },
+            spec("PROXY"),
+        ];
+
+        assert_eq!(
+            env_example(&vars),
+            "# API key for the upstream service\n\
+             #\n\
+             # Mount it as a file in production.\n\
+             # required\n\
+             # Or set API_KEY_FILE to the path of a file containing the value\n\
+             API_KEY=\n\
+             \n\
+             # LOG_LEVEL=info\n\
+             \n\
+             # default: Duration::from_secs(60)\n\
+             # TIMEOUT=\n\
+             \n\
+             # PROXY=\n"
+        );
+        assert_eq!(env_example(&[]), "");
+    }
+
+    #[test]
+    fn test_markdown_table() {
+        let vars = [
+            EnvVarSpec {
+                type_name: "u16",
+                default: Some("8080"),
+                doc: "Port the HTTP server listens on",
+                group: Some("Server"),
+                ..spec("PORT")
+            },
+            EnvVarSpec {
+                required: true,
+                from_file: true,
+                doc: "Connection string\nfor the database\n\nMust be | escaped.",
+                ..spec("DATABASE_URL")
+            },
+            EnvVarSpec {
+                type_name: "Vec<String>",
+                group: Some("Server"),
+                ..spec("ALLOWED_HOSTS")
+            },
+        ];
+
+        assert_eq!(
+            markdown_table(&vars),
+            "| Name | Type | Default | Required | `_FILE` | Description |\n\
+             | ---- | ---- | ------- | -------- | ------- | ----------- |\n\
+             | `DATABASE_URL` | `String` |  | yes | yes | Connection string for the database<br>Must be \\| escaped. |\n\
+             \n\
+             ### Server\n\
+             \n\
+             | Name | Type | Default | Required | `_FILE` | Description |\n\
+             | ---- | ---- | ------- | -------- | ------- | ----------- |\n\
+             | `PORT` | `u16` | `8080` | no | no | Port the HTTP server listens on |\n\
+             | `ALLOWED_HOSTS` | `Vec<String>` |  | no | no |  |\n"
+        );
+        assert_eq!(markdown_table(&[]), "");
+    }
+
+    #[test]
+    fn test_html_table() {
+        let vars = [EnvVarSpec {
+            type_name: "Vec<String>",
+            default: Some("\"a\""),
+            doc: "Hosts & ports\n\nComma separated",
+            group: Some("Server"),
+            ..spec("HOSTS")
+        }];
+
+        assert_eq!(
+            html_table(&vars),
+            "<h3>Server</h3>\n\
+             <table>\n\
+             <thead>\n\
+             <tr><th>Name</th><th>Type</th><th>Default</th><th>Required</th><th><code>_FILE</code></th><th>Description</th></tr>\n\
+             </thead>\n\
+             <tbody>\n\
+             <tr><td><code>HOSTS</code></td><td><code>Vec&lt;String&gt;</code></td><td><code>&quot;a&quot;</code></td><td>no</td><td>no</td><td>Hosts &amp; ports<br>Comma separated</td></tr>\n\
+             </tbody>\n\
+             </table>\n"
+        );
+    }
+
+    #[test]
+    fn test_json_schema() {
+        let vars = [
+            EnvVarSpec {
+                type_name: "Option<u16>",
+                aliases: &["HTTP_PORT"],
+                deprecated_names: &["SERVER_PORT"],
+                ..spec("PORT")
+            },
+            EnvVarSpec {
+                required: true,
+                from_file: true,
+                doc: "API key",
+                validations: &[Validation::NonEmpty, Validation::MaxLen(64)],
+                ..spec("API_KEY")
+            },
+            EnvVarSpec {
+                required: true,
+                validations: &[Validation::OneOf(&["\"debug\"", "\"info\""])],
+                ..spec("LOG_LEVEL")
+            },
+            EnvVarSpec {
+                type_name: "Vec<String>",
+                deserializer: Some("parse_list"),
+                validations: &[Validation::NonEmpty],
+                ..spec("HOSTS")
+            },
+        ];
+
+        let schema: Value = serde_json::from_str(&json_schema(&vars)).unwrap();
+        assert_eq!(schema["type"], "object");
+        let properties = &schema["properties"];
+        assert_eq!(
+            properties["PORT"],
+            json!({ "type": "string", "pattern": UNSIGNED_PATTERN })
+        );
+        assert_eq!(properties["HTTP_PORT"], properties["PORT"]);
+        assert_eq!(properties["SERVER_PORT"]["deprecated"], true);
+        assert_eq!(
+            properties["API_KEY"],
+            json!({
+                "type": "string",
+                "description": "API key",
+                "minLength": 1,
+                "maxLength": 64,
+            })
+        );
+        assert_eq!(
+            properties["API_KEY_FILE"]["description"],
+            "Path of a file containing the value of API_KEY"
+        );
+        assert_eq!(properties["LOG_LEVEL"]["enum"], json!(["debug", "info"]));
+        assert_eq!(properties["HOSTS"], json!({ "type": "string" }));
+
+        assert_eq!(schema["required"], json!(["LOG_LEVEL"]));
+        assert_eq!(
+            schema["allOf"],
+            json!([{ "anyOf": [{ "required": ["API_KEY"] }, { "required": ["API_KEY_FILE"] }] }])
+        );
+    }
+
+    #[test]
+    fn test_value_type() {
+        assert_eq!(value_type("u16"), "u16");
+        assert_eq!(value_type("Option<Secret<u16>>"), "u16");
+        assert_eq!(
+            value_type("std::option::Option<std::string::String>"),
+            "String"
+        );
+        assert_eq!(value_type("Vec<u16>"), "Vec<u16>");
+    }
+
+    #[test]
+    fn test_literal() {
+        assert_eq!(literal("\"debug\""), Some(json!("debug")));
+        assert_eq!(literal("-1_000"), Some(json!("-1000")));
+        assert_eq!(literal("true"), Some(json!("true")));
+        assert_eq!(literal("Level::Debug"), None);
+        assert_eq!(literal("\"a\\\"b\""), None);
+    }
+
+    fn deployment_vars() -> [EnvVarSpec; 4] {
+        [
+            EnvVarSpec {
+                field: "database_url",
+                required: true,
+                doc: "Primary database",
+                ..spec("APP_DATABASE_URL")
+            },
+            EnvVarSpec {
+                field: "port",
+                default: Some("8080"),
+                env_default: Some("8080"),
+                ..spec("APP_PORT")
+            },
+            EnvVarSpec {
+                field: "password",
+                required: true,
+                secret: true,
+                ..spec("APP_PASSWORD")
+            },
+            EnvVarSpec {
+                field: "api_key",
+                required: true,
+                from_file: true,
+                secret: true,
+                ..spec("APP_API_KEY")
+            },
+        ]
+    }
+
+    #[test]
+    fn test_kubernetes_env() {
+        assert_eq!(
+            kubernetes_env(&deployment_vars(), "app-secrets"),
+            "env:\n\
+             \x20 # Primary database\n\
+             \x20 # required\n\
+             \x20 - name: APP_DATABASE_URL\n\
+             \x20   value: \"\"\n\
+             \x20 # - name: APP_PORT\n\
+             \x20 #   value: \"8080\"\n\
+             \x20 # required\n\
+             \x20 - name: APP_PASSWORD\n\
+             \x20   valueFrom:\n\
+             \x20     secretKeyRef:\n\
+             \x20       name: app-secrets\n\
+             \x20       key: password\n\
+             \x20 # required\n\
+             \x20 - name: APP_API_KEY_FILE\n\
+             \x20   value: /etc/secrets/api-key\n\
+             # Mount the app-secrets Secret for the _FILE variables above:\n\
+             # volumeMounts:\n\
+             #   - name: secrets\n\
+             #     mountPath: /etc/secrets\n\
+             #     readOnly: true\n\
+             # volumes:\n\
+             #   - name: secrets\n\
+             #     secret:\n\
+             #       secretName: app-secrets\n\
+             #       items:\n\
+             #         - key: api-key\n\
+             #           path: api-key\n"
+        );
+        assert_eq!(kubernetes_env(&[], "app-secrets"), "env: []\n");
+    }
+
+    #[test]
+    fn test_compose_env() {
+        assert_eq!(
+            compose_env(&deployment_vars()),
+            "environment:\n\
+             \x20 # Primary database\n\
+             \x20 # required\n\
+             \x20 APP_DATABASE_URL: \"\"\n\
+             \x20 # APP_PORT: \"8080\"\n\
+             \x20 # required\n\
+             \x20 # Secret: interpolated when Compose runs; use from_file to mount it as a Compose secret\n\
+             \x20 APP_PASSWORD: \"${APP_PASSWORD:?APP_PASSWORD is required}\"\n\
+             \x20 # required\n\
+             \x20 APP_API_KEY_FILE: /run/secrets/api-key\n\
+             secrets:\n\
+             \x20 - api-key\n\
+             # Top-level definitions of the secrets above:\n\
+             # secrets:\n\
+             #   api-key:\n\
+             #     file: ./secrets/api-key\n"
+        );
+        assert_eq!(compose_env(&[]), "environment: {}\n");
+    }
+
+    #[test]
+    fn test_yaml_value() {
+        let value = |env_default| {
+            yaml_value(&EnvVarSpec {
+                env_default,
+                ..spec("X")
+            })
+        };
+        assert_eq!(value(None), "\"\"");
+        assert_eq!(value(Some("localhost")), "\"localhost\"");
+        assert_eq!(value(Some("a \"b\"")), "\"a \\\"b\\\"\"");
+    }
+
+    #[test]
+    fn test_check_file() {
+        use tempfile::NamedTempFile;
+
+        let file = NamedTempFile::new().unwrap();
+        std::fs::write(file.path(), "PORT=\n").unwrap();
+        assert!(check_file(file.path(), "PORT=\n").is_ok());
+
+        let err = check_file(file.path(), "# PORT=8080\n").unwrap_err();
+        assert!(matches!(&err, CheckError::OutOfDate { path } if path == file.path()));
+        assert_eq!(
+            err.to_string(),
+            format!("{} is out of date", file.path().display())
+        );
+
+        let missing = file.path().with_extension("missing");
+        let err = check_file(&missing, "PORT=\n").unwrap_err();
+        assert!(
+            matches!(&err, CheckError::Read { source, .. } if source.kind() == io::ErrorKind::NotFound)
+        );
+    }
+
+    #[test]
+    fn test_expression_defaults_are_commented() {
+        let vars = [
+            EnvVarSpec {
+                default: Some("Duration::from_secs(60)"),
+                doc: "Request timeout",
+                ..spec("TIMEOUT")
+            },
+            EnvVarSpec {
+                default: Some("info"),
+                env_default: Some("info"),
+                ..spec("LOG_LEVEL")
+            },
+        ];
+        assert_eq!(
+            compose_env(&vars),
+            "environment: {}\n\
+             \x20 # Request timeout\n\
+             \x20 # default: Duration::from_secs(60)\n\
+             \x20 # TIMEOUT: \"\"\n\
+             \x20 # LOG_LEVEL: \"info\"\n"
+        );
+        assert_eq!(
+            kubernetes_env(&vars, "app-secrets"),
+            "env: []\n\
+             \x20 # Request timeout\n\
+             \x20 # default: Duration::from_secs(60)\n\
+             \x20 # - name: TIMEOUT\n\
+             \x20 #   value: \"\"\n\
+             \x20 # - name: LOG_LEVEL\n\
+             \x20 #   value: \"info\"\n"
+        );
+    }
+
+    #[test]
+    fn test_optional_sections_are_empty() {
+        let vars = [EnvVarSpec {
+            field: "api_key",
+            from_file: true,
+            secret: true,
+            ..spec("APP_API_KEY")
+        }];
+        assert_eq!(
+            compose_env(&vars),
+            "environment: {}\n\
+             \x20 # APP_API_KEY_FILE: /run/secrets/api-key\n\
+             secrets: []\n\
+             \x20 # - api-key\n\
+             # Top-level definitions of the secrets above:\n\
+             # secrets:\n\
+             #   api-key:\n\
+             #     file: ./secrets/api-key\n"
+        );
+        assert!(kubernetes_env(&vars, "app-secrets")
+            .starts_with("env: []\n  # - name: APP_API_KEY_FILE\n"));
+    }
+
+    #[test]
+    fn test_is_dns_subdomain() {
+        assert!(is_dns_subdomain("app-secrets"));
+        assert!(is_dns_subdomain("app.secrets-2"));
+        assert!(!is_dns_subdomain(""));
+        assert!(!is_dns_subdomain("App-Secrets"));
+        assert!(!is_dns_subdomain("-secrets"));
+        assert!(!is_dns_subdomain("secrets."));
+        assert!(!is_dns_subdomain("app secrets"));
+        assert!(!is_dns_subdomain("x\n    key: y"));
+        assert!(!is_dns_subdomain(&"a".repeat(254)));
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid Kubernetes Secret name")]
+    fn test_kubernetes_env_rejects_invalid_secret_name() {
+        kubernetes_env(&deployment_vars(), "app: secrets");
+    }
+}
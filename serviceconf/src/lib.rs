@@ -3,11 +3,26 @@
 #[doc(hidden)]
 pub mod de;
 
+#[doc(hidden)]
+pub mod validate;
+
+pub mod generate;
+
+mod deprecation;
 mod error;
+mod provenance;
+mod secret;
+mod spec;
 
-pub use error::ServiceConfError;
+pub use deprecation::{set_deprecation_hook, DeprecatedUsage};
+pub use error::{Constraint, ServiceConfError};
+pub use provenance::{NameKind, Origin, Provenance, Source};
+pub use secret::Secret;
 pub use serviceconf_derive::ServiceConf;
+pub use spec::{EnvVarSpec, ServiceConfSpec, Validation};
 
-// Re-export for macro-generated code
-#[doc(hidden)]
+/// The `anyhow` crate, re-exported with the `anyhow` feature so that
+/// `#[conf(error = serviceconf::anyhow::Error)]` restores the `anyhow::Result`
+/// signature of earlier versions without a direct dependency
+#[cfg(feature = "anyhow")]
 pub use anyhow;
@@ -0,0 +1,202 @@
+//! Where each loaded configuration value came from.
+//!
+//! Returned by the derived `from_env_with_provenance()` alongside the
+//! configuration, following the same resolution order as `from_env()`.
+
+use std::fmt;
+
+/// Origin of every loaded field of a configuration, in declaration order
+///
+/// Fields marked `#[conf(skip)]` are not listed.
+///
+/// ```
+/// use serviceconf::{ServiceConf, Source};
+///
+/// #[derive(ServiceConf)]
+/// struct Config {
+///     #[conf(default = 8080)]
+///     pub port: u16,
+/// }
+///
+/// # std::env::remove_var("PORT");
+/// let (config, provenance) = Config::from_env_with_provenance().unwrap();
+/// assert_eq!(
+///     provenance.get("port").unwrap().source,
+///     Source::Default { expr: "8080".to_string() }
+/// );
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Provenance {
+    origins: Vec<Origin>,
+}
+
+impl Provenance {
+    /// Create a provenance record (used by macro-generated code)
+    #[doc(hidden)]
+    pub fn new(origins: Vec<Origin>) -> Self {
+        Self { origins }
+    }
+
+    /// Origin of the field with the given name (without any `r#` prefix)
+    pub fn get(&self, field: &str) -> Option<&Origin> {
+        self.origins.iter().find(|origin| origin.field == field)
+    }
+
+    /// Origins of every field, in declaration order
+    pub fn iter(&self) -> std::slice::Iter<'_, Origin> {
+        self.origins.iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Provenance {
+    type Item = &'a Origin;
+    type IntoIter = std::slice::Iter<'a, Origin>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Writes one `field: source` line per field
+impl fmt::Display for Provenance {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, origin) in self.origins.iter().enumerate() {
+            if i > 0 {
+                f.write_str("\n")?;
+            }
+            write!(f, "{}: {}", origin.field, origin.source)?;
+        }
+        Ok(())
+    }
+}
+
+/// Origin of a single field's value
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Origin {
+    /// Field name (without any `r#` prefix)
+    pub field: &'static str,
+    /// Primary environment variable name of the field (prefix applied)
+    pub env_var: &'static str,
+    /// Where the value came from
+    pub source: Source,
+}
+
+impl Origin {
+    /// Create an origin entry (used by macro-generated code)
+    #[doc(hidden)]
+    pub fn new(field: &'static str, env_var: &'static str, source: Source) -> Self {
+        Self {
+            field,
+            env_var,
+            source,
+        }
+    }
+}
+
+/// Layer a value was resolved from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Source {
+    /// Read directly from an environment variable
+    Env {
+        /// Environment variable the value was read from
+        name: String,
+        /// Whether `name` is the primary name, an alias or a deprecated name
+        kind: NameKind,
+    },
+    /// Read from the file named by a `{NAME}_FILE` environment variable
+    File {
+        /// The `{NAME}_FILE` variable that named the file
+        name: String,
+        /// Path of the file
+        path: String,
+        /// Whether `{NAME}` is the primary name, an alias or a deprecated name
+        kind: NameKind,
+    },
+    /// No variable was set and the field's default was used
+    Default {
+        /// The default as written in the attribute (e.g. `8080`, `default_port()`)
+        expr: String,
+    },
+    /// No variable was set and the `Option<T>` field was left `None`
+    Unset,
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Source::Env { name, kind } => write!(f, "env {}{}", name, kind.suffix()),
+            Source::File { name, path, kind } => {
+                write!(f, "file {} (via {}){}", path, name, kind.suffix())
+            }
+            Source::Default { expr } => write!(f, "default {}", expr),
+            Source::Unset => f.write_str("unset"),
+        }
+    }
+}
+
+/// Which of a field's names a value was found under
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NameKind {
+    /// The field's primary name
+    Primary,
+    /// A name declared with `#[conf(alias = "...")]`
+    Alias,
+    /// A name declared with `#[conf(deprecated_name = "...")]`
+    Deprecated,
+}
+
+impl NameKind {
+    fn suffix(self) -> &'static str {
+        match self {
+            NameKind::Primary => "",
+            NameKind::Alias => " (alias)",
+            NameKind::Deprecated => " (deprecated name)",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        let provenance = Provenance::new(vec![
+            Origin::new(
+                "port",
+                "PORT",
+                Source::Env {
+                    name: "HTTP_PORT".to_string(),
+                    kind: NameKind::Alias,
+                },
+            ),
+            Origin::new(
+                "api_key",
+                "API_KEY",
+                Source::File {
+                    name: "API_KEY_FILE".to_string(),
+                    path: "/run/secrets/api-key".to_string(),
+                    kind: NameKind::Primary,
+                },
+            ),
+            Origin::new(
+                "host",
+                "HOST",
+                Source::Default {
+                    expr: "\"localhost\".to_string()".to_string(),
+                },
+            ),
+            Origin::new("proxy", "PROXY", Source::Unset),
+        ]);
+
+        assert_eq!(
+            provenance.to_string(),
+            "port: env HTTP_PORT (alias)\n\
+             api_key: file /run/secrets/api-key (via API_KEY_FILE)\n\
+             host: default \"localhost\".to_string()\n\
+             proxy: unset"
+        );
+        assert_eq!(provenance.get("proxy").unwrap().env_var, "PROXY");
+        assert!(provenance.get("missing").is_none());
+    }
+}
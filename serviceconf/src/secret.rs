@@ -0,0 +1,139 @@
+//! Wrapper type for secret configuration values.
+//!
+//! [`Secret`] keeps passwords, tokens and keys out of logs and wipes them from
+//! memory when dropped.
+
+use std::fmt;
+use std::str::FromStr;
+use zeroize::Zeroize;
+
+/// A configuration value that is never printed and is zeroized on drop
+///
+/// `Debug` and `Display` print `***` instead of the content, and the value can
+/// only be read through [`Secret::expose`], which makes every use easy to spot
+/// in review. The wrapped value is overwritten with zeros when the `Secret` is
+/// dropped.
+///
+/// `Secret<T>` parses like `T`, so it works anywhere a `FromStr` field does,
+/// including `#[conf(from_file)]`, `Option<Secret<T>>`, `default_str` and
+/// `default`. The derive macro recognizes it however the type is written
+/// (imported, aliased or by full path): such fields are masked in error
+/// messages, and a `deserializer` on them returns the inner `T`.
+///
+/// ```
+/// use serviceconf::{Secret, ServiceConf};
+///
+/// #[derive(Debug, ServiceConf)]
+/// struct Config {
+///     #[conf(from_file)]
+///     pub api_key: Secret<String>,
+/// }
+///
+/// # std::env::set_var("API_KEY", "key-123");
+/// let config = Config::from_env().unwrap();
+/// assert_eq!(format!("{:?}", config), "Config { api_key: *** }");
+/// assert_eq!(config.api_key.expose(), "key-123");
+/// # std::env::remove_var("API_KEY");
+/// ```
+///
+/// Only the `Secret` itself is zeroized: copies made by the caller from
+/// [`Secret::expose`], and the environment variable itself, are not.
+pub struct Secret<T: Zeroize>(T);
+
+impl<T: Zeroize> Secret<T> {
+    /// Wrap a value
+    pub fn new(value: T) -> Self {
+        Self(value)
+    }
+
+    /// Reveal the wrapped value
+    pub fn expose(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T: Zeroize> From<T> for Secret<T> {
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<T> FromStr for Secret<T>
+where
+    T: Zeroize + FromStr,
+{
+    type Err = T::Err;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.parse().map(Self::new)
+    }
+}
+
+impl<T: Zeroize + Clone> Clone for Secret<T> {
+    fn clone(&self) -> Self {
+        Self::new(self.0.clone())
+    }
+}
+
+impl<T: Zeroize + Default> Default for Secret<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T: Zeroize> fmt::Debug for Secret<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("***")
+    }
+}
+
+impl<T: Zeroize> fmt::Display for Secret<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("***")
+    }
+}
+
+impl<T: Zeroize> Drop for Secret<T> {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_never_printed() {
+        let secret: Secret<String> = "hunter2".parse().unwrap();
+        assert_eq!(format!("{:?}", secret), "***");
+        assert_eq!(secret.to_string(), "***");
+        assert_eq!(format!("{:?}", Some(secret.clone())), "Some(***)");
+        assert_eq!(secret.expose(), "hunter2");
+    }
+
+    #[test]
+    fn test_parse_error_is_forwarded() {
+        let err = "abc".parse::<Secret<u32>>().unwrap_err();
+        assert_eq!(err, "abc".parse::<u32>().unwrap_err());
+    }
+
+    #[test]
+    fn test_zeroized_on_drop() {
+        struct Tracked(Arc<AtomicBool>);
+
+        impl Zeroize for Tracked {
+            fn zeroize(&mut self) {
+                self.0.store(true, Ordering::SeqCst);
+            }
+        }
+
+        let zeroized = Arc::new(AtomicBool::new(false));
+        let secret = Secret::new(Tracked(zeroized.clone()));
+        assert!(!zeroized.load(Ordering::SeqCst));
+        drop(secret);
+        assert!(zeroized.load(Ordering::SeqCst));
+    }
+}
@@ -0,0 +1,311 @@
+//! Static description of the environment variables a configuration reads.
+//!
+//! Returned by [`ServiceConfSpec::env_vars`] so tooling, tests and admin endpoints
+//! can list variables without repeating what the `#[conf]` attributes already say.
+
+use crate::generate;
+use std::path::Path;
+
+/// Metadata about the environment variables of a configuration struct
+///
+/// Implemented by `#[derive(ServiceConf)]`. Only [`env_vars`](Self::env_vars) is
+/// generated; the other methods render it with the functions in
+/// [`crate::generate`]. Bring the trait into scope to call them, e.g.
+/// `use serviceconf::ServiceConfSpec;`.
+///
+/// ```
+/// use serviceconf::{ServiceConf, ServiceConfSpec};
+///
+/// #[derive(ServiceConf)]
+/// struct Config {
+///     #[conf(default = 8080)]
+///     pub port: u16,
+/// }
+///
+/// assert_eq!(Config::env_vars()[0].name, "PORT");
+/// assert!(Config::env_example().contains("# PORT=8080"));
+/// ```
+pub trait ServiceConfSpec {
+    /// Description of every environment variable read by `from_env()`, in
+    /// field order
+    fn env_vars() -> &'static [EnvVarSpec];
+
+    /// Example env file listing every variable with its doc comment and
+    /// default, or a `# required` marker
+    ///
+    /// See [`generate::env_example`] for the format.
+    fn env_example() -> String {
+        generate::env_example(Self::env_vars())
+    }
+
+    /// Check that a checked-in example env file matches
+    /// [`env_example`](Self::env_example), e.g. from a test or CI step
+    ///
+    /// See [`generate::check_file`] for the errors.
+    ///
+    /// ```no_run
+    /// use serviceconf::{ServiceConf, ServiceConfSpec};
+    ///
+    /// #[derive(ServiceConf)]
+    /// struct Config {
+    ///     pub database_url: String,
+    /// }
+    ///
+    /// // In a test of the application
+    /// if let Err(e) = Config::check_env_example(".env.example") {
+    ///     panic!("{}; regenerate it from Config::env_example()", e);
+    /// }
+    /// ```
+    fn check_env_example(path: impl AsRef<Path>) -> Result<(), generate::CheckError> {
+        generate::check_file(path, &Self::env_example())
+    }
+
+    /// Markdown reference table of every variable, grouped by `group`
+    ///
+    /// See [`generate::markdown_table`] for the format.
+    fn markdown_table() -> String {
+        generate::markdown_table(Self::env_vars())
+    }
+
+    /// HTML reference table of every variable, grouped by `group`
+    ///
+    /// See [`generate::html_table`] for the format.
+    fn html_table() -> String {
+        generate::html_table(Self::env_vars())
+    }
+
+    /// JSON Schema of the environment variables, for validating a deployment's
+    /// environment without running the application
+    ///
+    /// See [`generate::json_schema`] for what is checked.
+    fn json_schema() -> String {
+        generate::json_schema(Self::env_vars())
+    }
+
+    /// Kubernetes container `env:` block reading secrets from the Secret named
+    /// `secret_name`
+    ///
+    /// See [`generate::kubernetes_env`] for the layout.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `secret_name` is not a valid Kubernetes object name.
+    fn kubernetes_env(secret_name: &str) -> String {
+        generate::kubernetes_env(Self::env_vars(), secret_name)
+    }
+
+    /// docker-compose service `environment:` and `secrets:` sections
+    ///
+    /// See [`generate::compose_env`] for the layout.
+    fn compose_env() -> String {
+        generate::compose_env(Self::env_vars())
+    }
+}
+
+/// Description of one environment variable read by a configuration struct
+///
+/// One entry is generated per loaded field (fields marked `#[conf(skip)]` are
+/// not listed), in declaration order. Names have the struct prefix applied;
+/// types, defaults and deserializers are recorded as written in the source.
+///
+/// ```
+/// use serviceconf::{ServiceConf, ServiceConfSpec};
+///
+/// #[derive(ServiceConf)]
+/// #[conf(prefix = "APP_", name_consts)]
+/// struct Config {
+///     /// Port the HTTP server listens on
+///     #[conf(default = 8080)]
+///     pub port: u16,
+/// }
+///
+/// let spec = &Config::env_vars()[0];
+/// assert_eq!(spec.name, "APP_PORT");
+/// assert_eq!(spec.name, Config::PORT);
+/// assert_eq!(spec.type_name, "u16");
+/// assert_eq!(spec.default, Some("8080"));
+/// assert_eq!(spec.env_default, Some("8080"));
+/// assert_eq!(spec.doc, "Port the HTTP server listens on");
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct EnvVarSpec {
+    /// Field name (without any `r#` prefix)
+    pub field: &'static str,
+    /// Primary environment variable name
+    pub name: &'static str,
+    /// Fallback names declared with `alias`
+    pub aliases: &'static [&'static str],
+    /// Retired names declared with `deprecated_name`
+    pub deprecated_names: &'static [&'static str],
+    /// Field type as written (e.g. `Option<String>`)
+    pub type_name: &'static str,
+    /// Whether loading fails when the variable is not set
+    ///
+    /// `false` for `Option<T>` fields and fields with a default.
+    pub required: bool,
+    /// Default as written in the attribute (e.g. `8080`, `default_port()`), for
+    /// display
+    pub default: Option<&'static str>,
+    /// Default in environment variable syntax: the `default_str` value, or a
+    /// literal `default` on a field parsed with `FromStr` (e.g. `8080`, `true`)
+    ///
+    /// `None` when the default is computed by a Rust expression
+    /// (`Duration::from_secs(60)`, `Default::default()`, `default_fn`), since
+    /// the macro cannot know what it evaluates to; see [`env_default_fn`](Self::env_default_fn).
+    pub env_default: Option<&'static str>,
+    /// Function rendering a computed default in environment variable syntax,
+    /// for fields with `with = module`: it evaluates the default and passes it
+    /// to `module::to_env_string`
+    ///
+    /// Use [`env_default_value`](Self::env_default_value) to get either form.
+    pub env_default_fn: Option<fn() -> String>,
+    /// Whether the `{NAME}_FILE` variant is read as well
+    pub from_file: bool,
+    /// Validation rules declared on the field, in declaration order
+    pub validations: &'static [Validation],
+    /// Custom parser as written: the `deserializer` expression, or
+    /// `module::parse` for `with = module`
+    pub deserializer: Option<&'static str>,
+    /// Whether the value is a secret (`secret`, `from_file` or `Secret<T>`)
+    pub secret: bool,
+    /// The field's doc comment, with leading indentation removed
+    pub doc: &'static str,
+    /// Heading the variable is listed under in generated reference tables,
+    /// declared with `group`
+    pub group: Option<&'static str>,
+}
+
+/// Compares every field, except that `env_default_fn` is only compared by
+/// presence, since function addresses are not guaranteed to be unique.
+impl PartialEq for EnvVarSpec {
+    fn eq(&self, other: &Self) -> bool {
+        self.field == other.field
+            && self.name == other.name
+            && self.aliases == other.aliases
+            && self.deprecated_names == other.deprecated_names
+            && self.type_name == other.type_name
+            && self.required == other.required
+            && self.default == other.default
+            && self.env_default == other.env_default
+            && self.env_default_fn.is_some() == other.env_default_fn.is_some()
+            && self.from_file == other.from_file
+            && self.validations == other.validations
+            && self.deserializer == other.deserializer
+            && self.secret == other.secret
+            && self.doc == other.doc
+            && self.group == other.group
+    }
+}
+
+impl Eq for EnvVarSpec {}
+
+impl EnvVarSpec {
+    /// Every environment variable name this field may be read from, in lookup
+    /// order (primary name, aliases, deprecated names), without `_FILE` variants
+    pub fn names(&self) -> impl Iterator<Item = &'static str> {
+        std::iter::once(self.name)
+            .chain(self.aliases.iter().copied())
+            .chain(self.deprecated_names.iter().copied())
+    }
+
+    /// Default in environment variable syntax: [`env_default`](Self::env_default),
+    /// or the output of [`env_default_fn`](Self::env_default_fn)
+    ///
+    /// `None` when the field has no default or its default cannot be written
+    /// as a variable value.
+    pub fn env_default_value(&self) -> Option<String> {
+        match (self.env_default, self.env_default_fn) {
+            (Some(default), _) => Some(default.to_string()),
+            (None, Some(render)) => Some(render()),
+            (None, None) => None,
+        }
+    }
+}
+
+/// A validation rule declared on a field, with values as written in the attribute
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Validation {
+    /// `range(min = ..., max = ...)`: inclusive bounds, either of which may be omitted
+    Range {
+        /// Lower bound as written
+        min: Option<&'static str>,
+        /// Upper bound as written
+        max: Option<&'static str>,
+    },
+    /// `min_len = n`: minimum number of elements (characters for strings)
+    MinLen(usize),
+    /// `max_len = n`: maximum number of elements (characters for strings)
+    MaxLen(usize),
+    /// `non_empty`: at least one element (character for strings)
+    NonEmpty,
+    /// `one_of = [...]`: allowed values as written (e.g. `"debug"`, `8080`)
+    OneOf(&'static [&'static str]),
+    /// `regex = "..."`: pattern the string value must match
+    Regex(&'static str),
+    /// `validate = path`: path of the custom validation function
+    Custom(&'static str),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_names() {
+        let spec = EnvVarSpec {
+            field: "port",
+            name: "PORT",
+            aliases: &["HTTP_PORT"],
+            deprecated_names: &["SERVER_PORT"],
+            type_name: "u16",
+            required: true,
+            default: None,
+            env_default: None,
+            env_default_fn: None,
+            from_file: false,
+            validations: &[],
+            deserializer: None,
+            secret: false,
+            doc: "",
+            group: None,
+        };
+        assert_eq!(
+            spec.names().collect::<Vec<_>>(),
+            ["PORT", "HTTP_PORT", "SERVER_PORT"]
+        );
+    }
+
+    #[test]
+    fn test_env_default_value() {
+        let spec = EnvVarSpec {
+            field: "timeout",
+            name: "TIMEOUT",
+            aliases: &[],
+            deprecated_names: &[],
+            type_name: "Duration",
+            required: false,
+            default: Some("Duration::from_secs(60)"),
+            env_default: None,
+            env_default_fn: None,
+            from_file: false,
+            validations: &[],
+            deserializer: Some("secs::parse"),
+            secret: false,
+            doc: "",
+            group: None,
+        };
+        assert_eq!(spec.env_default_value(), None);
+
+        let spec = EnvVarSpec {
+            env_default_fn: Some(|| "60".to_string()),
+            ..spec
+        };
+        assert_eq!(spec.env_default_value().as_deref(), Some("60"));
+
+        let spec = EnvVarSpec {
+            env_default: Some("30"),
+            ..spec
+        };
+        assert_eq!(spec.env_default_value().as_deref(), Some("30"));
+    }
+}
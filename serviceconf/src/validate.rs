@@ -0,0 +1,434 @@
+//! Validation functions for parsed configuration values.
+//!
+//! This module provides internal functions used by the `ServiceConf` derive macro
+//! to check field values against declarative rules (`range`, `min_len`, `max_len`,
+//! `non_empty`, `one_of`, `regex`) after they have been parsed, and cross-field
+//! constraints (`requires`, `conflicts_with`, `required_if`) before parsing.
+
+use crate::de::{self, EnvVar};
+use crate::error::{Constraint, ServiceConfError, HIDDEN_MESSAGE};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use std::fmt::{Debug, Display};
+
+/// Check that a value lies within an inclusive range
+///
+/// The offending value is included in the message unless `secret` is set.
+/// Used by the derive macro for `#[conf(range(min = ..., max = ...))]`.
+#[doc(hidden)]
+pub fn range<T>(
+    name: &str,
+    secret: bool,
+    value: &T,
+    min: Option<T>,
+    max: Option<T>,
+) -> Result<(), ServiceConfError>
+where
+    T: PartialOrd + Display,
+{
+    let below = min.as_ref().is_some_and(|min| value < min);
+    let above = max.as_ref().is_some_and(|max| value > max);
+    if !below && !above {
+        return Ok(());
+    }
+
+    let expected = match (&min, &max) {
+        (Some(min), Some(max)) => format!("between {} and {}", min, max),
+        (Some(min), None) => format!("at least {}", min),
+        (None, Some(max)) => format!("at most {}", max),
+        (None, None) => unreachable!(),
+    };
+    let message = if secret {
+        format!("must be {}", expected)
+    } else {
+        format!("must be {}, got {}", expected, value)
+    };
+    Err(ServiceConfError::validation(name, "range", message))
+}
+
+/// Turn the result of a custom `validate` function into a validation error
+///
+/// The function's message may quote the value, so it is replaced with a generic
+/// one when `secret` is set. Used by the derive macro for
+/// `#[conf(validate = function)]`.
+#[doc(hidden)]
+pub fn custom<E>(
+    name: &str,
+    secret: bool,
+    rule: &str,
+    result: Result<(), E>,
+) -> Result<(), ServiceConfError>
+where
+    E: Display,
+{
+    result.map_err(|e| {
+        let message = if secret {
+            HIDDEN_MESSAGE.to_string()
+        } else {
+            e.to_string()
+        };
+        ServiceConfError::validation(name, rule, message)
+    })
+}
+
+/// Check that a value has at least `min` elements (characters for strings)
+///
+/// Used by the derive macro for `#[conf(min_len = ...)]`.
+#[doc(hidden)]
+pub fn min_len<V>(name: &str, value: &V, min: usize) -> Result<(), ServiceConfError>
+where
+    V: Length + ?Sized,
+{
+    let len = value.length();
+    if len >= min {
+        return Ok(());
+    }
+    Err(ServiceConfError::validation(
+        name,
+        "min_len",
+        format!("length must be at least {}, got {}", min, len),
+    ))
+}
+
+/// Check that a value has at most `max` elements (characters for strings)
+///
+/// Used by the derive macro for `#[conf(max_len = ...)]`.
+#[doc(hidden)]
+pub fn max_len<V>(name: &str, value: &V, max: usize) -> Result<(), ServiceConfError>
+where
+    V: Length + ?Sized,
+{
+    let len = value.length();
+    if len <= max {
+        return Ok(());
+    }
+    Err(ServiceConfError::validation(
+        name,
+        "max_len",
+        format!("length must be at most {}, got {}", max, len),
+    ))
+}
+
+/// Check that a value is not empty
+///
+/// Used by the derive macro for `#[conf(non_empty)]`.
+#[doc(hidden)]
+pub fn non_empty<V>(name: &str, value: &V) -> Result<(), ServiceConfError>
+where
+    V: Length + ?Sized,
+{
+    if value.length() > 0 {
+        return Ok(());
+    }
+    Err(ServiceConfError::validation(
+        name,
+        "non_empty",
+        "must not be empty",
+    ))
+}
+
+/// Check that a value is one of the allowed values
+///
+/// Used by the derive macro for `#[conf(one_of = [...])]`.
+#[doc(hidden)]
+pub fn one_of<T, U>(name: &str, value: &T, allowed: &[U]) -> Result<(), ServiceConfError>
+where
+    T: PartialEq<U>,
+    U: Debug,
+{
+    if allowed.iter().any(|candidate| value == candidate) {
+        return Ok(());
+    }
+    let expected = allowed
+        .iter()
+        .map(|candidate| format!("{:?}", candidate))
+        .collect::<Vec<_>>()
+        .join(", ");
+    Err(ServiceConfError::validation(
+        name,
+        "one_of",
+        format!("must be one of {}", expected),
+    ))
+}
+
+/// Check that a string value matches a regular expression
+///
+/// Compiled patterns are cached per pattern string.
+/// Used by the derive macro for `#[conf(regex = "...")]`.
+#[cfg(feature = "regex")]
+#[doc(hidden)]
+pub fn regex<V>(name: &str, value: &V, pattern: &'static str) -> Result<(), ServiceConfError>
+where
+    V: AsRef<str> + ?Sized,
+{
+    use std::sync::{Mutex, OnceLock};
+
+    static CACHE: OnceLock<Mutex<HashMap<&'static str, regex::Regex>>> = OnceLock::new();
+
+    let re = {
+        let mut cache = CACHE
+            .get_or_init(Default::default)
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        match cache.get(pattern) {
+            Some(re) => re.clone(),
+            None => {
+                let re = regex::Regex::new(pattern).map_err(|e| {
+                    ServiceConfError::validation(
+                        name,
+                        "regex",
+                        format!("invalid pattern {:?}: {}", pattern, e),
+                    )
+                })?;
+                cache.insert(pattern, re.clone());
+                re
+            }
+        }
+    };
+
+    if re.is_match(value.as_ref()) {
+        return Ok(());
+    }
+    Err(ServiceConfError::validation(
+        name,
+        "regex",
+        format!("must match pattern {:?}", pattern),
+    ))
+}
+
+/// Marker reported when `#[conf(regex = "...")]` is used without the `regex` feature
+#[cfg(not(feature = "regex"))]
+#[doc(hidden)]
+#[diagnostic::on_unimplemented(
+    message = "`#[conf(regex = \"...\")]` requires the `regex` feature of serviceconf",
+    label = "enable the `regex` feature to validate this field"
+)]
+pub trait RegexFeatureRequired {}
+
+/// Stub that fails to compile with a clear message when the `regex` feature is disabled
+#[cfg(not(feature = "regex"))]
+#[doc(hidden)]
+pub fn regex<V>(_name: &str, _value: &V, _pattern: &'static str) -> Result<(), ServiceConfError>
+where
+    V: AsRef<str> + RegexFeatureRequired + ?Sized,
+{
+    unreachable!()
+}
+
+/// Check that `other` is set whenever `var` is set
+///
+/// Used by the derive macro for `#[conf(requires = "field")]`.
+#[doc(hidden)]
+pub fn requires(var: &EnvVar<'_>, other: &EnvVar<'_>) -> Result<(), ServiceConfError> {
+    if de::is_set(var) && !de::is_set(other) {
+        return Err(ServiceConfError::constraint(
+            var.name,
+            other.name,
+            Constraint::Requires,
+        ));
+    }
+    Ok(())
+}
+
+/// Check that `var` and `other` are not both set
+///
+/// Used by the derive macro for `#[conf(conflicts_with = "field")]`.
+#[doc(hidden)]
+pub fn conflicts_with(var: &EnvVar<'_>, other: &EnvVar<'_>) -> Result<(), ServiceConfError> {
+    if de::is_set(var) && de::is_set(other) {
+        return Err(ServiceConfError::constraint(
+            var.name,
+            other.name,
+            Constraint::ConflictsWith,
+        ));
+    }
+    Ok(())
+}
+
+/// Check that `var` is set whenever the raw value of `other` equals `value`
+///
+/// A `_FILE` variant of `other` that cannot be read does not trigger the
+/// constraint, since loading `other` already reports the read error.
+///
+/// Used by the derive macro for `#[conf(required_if(field = "value"))]`.
+#[doc(hidden)]
+pub fn required_if(
+    var: &EnvVar<'_>,
+    other: &EnvVar<'_>,
+    value: &str,
+) -> Result<(), ServiceConfError> {
+    if de::is_set(var) {
+        return Ok(());
+    }
+    match de::peek_env_value(other) {
+        Ok(Some(env)) if env.value == value => Err(ServiceConfError::constraint(
+            var.name,
+            other.name,
+            Constraint::RequiredIf {
+                value: value.to_string(),
+            },
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Number of elements in a value, as checked by `min_len`, `max_len` and `non_empty`
+///
+/// Strings are measured in characters rather than bytes.
+#[doc(hidden)]
+pub trait Length {
+    /// Number of elements (characters for strings)
+    fn length(&self) -> usize;
+}
+
+impl Length for str {
+    fn length(&self) -> usize {
+        self.chars().count()
+    }
+}
+
+impl Length for String {
+    fn length(&self) -> usize {
+        self.as_str().length()
+    }
+}
+
+impl<T> Length for [T] {
+    fn length(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<T> Length for Vec<T> {
+    fn length(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<T> Length for VecDeque<T> {
+    fn length(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<K, V, S> Length for HashMap<K, V, S> {
+    fn length(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<T, S> Length for HashSet<T, S> {
+    fn length(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<K, V> Length for BTreeMap<K, V> {
+    fn length(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<T> Length for BTreeSet<T> {
+    fn length(&self) -> usize {
+        self.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_range() {
+        assert!(range("PORT", false, &8080u16, Some(1024), Some(65535)).is_ok());
+        assert!(range("PORT", false, &1024u16, Some(1024), None).is_ok());
+
+        let err = range("PORT", false, &80u16, Some(1024), Some(65535)).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Environment variable 'PORT' failed range validation: must be between 1024 and 65535, got 80"
+        );
+
+        let err = range("RATIO", false, &1.5f64, None, Some(1.0)).unwrap_err();
+        assert!(err.to_string().ends_with("must be at most 1, got 1.5"));
+
+        let err = range("PIN", true, &1234u32, Some(100_000), None).unwrap_err();
+        assert!(err.to_string().ends_with("must be at least 100000"));
+        assert!(!err.to_string().contains("1234"));
+    }
+
+    #[test]
+    fn test_custom() {
+        assert!(custom("POOL", false, "check", Ok::<_, String>(())).is_ok());
+
+        let err = custom("POOL", false, "check", Err("got 3, not a power of two")).unwrap_err();
+        assert!(err.to_string().ends_with("got 3, not a power of two"));
+
+        let err = custom("TOKEN", true, "check", Err("'hunter2' is too weak")).unwrap_err();
+        assert!(matches!(
+            err,
+            ServiceConfError::Validation { ref rule, .. } if rule == "check"
+        ));
+        assert!(!err.to_string().contains("hunter2"));
+    }
+
+    #[test]
+    fn test_lengths() {
+        assert!(min_len("NAME", "héllo", 5).is_ok());
+        assert!(max_len("NAME", "héllo", 5).is_ok());
+        assert!(min_len("HOSTS", &vec!["a"], 2).is_err());
+        assert!(max_len("HOSTS", &vec!["a", "b", "c"], 2).is_err());
+
+        assert!(non_empty("URL", "x").is_ok());
+        let err = non_empty("URL", &String::new()).unwrap_err();
+        assert!(matches!(
+            err,
+            ServiceConfError::Validation { ref rule, .. } if rule == "non_empty"
+        ));
+    }
+
+    #[test]
+    fn test_one_of() {
+        let level = String::from("info");
+        assert!(one_of("LOG_LEVEL", &level, &["debug", "info"]).is_ok());
+
+        let level = String::from("verbose");
+        let err = one_of("LOG_LEVEL", &level, &["debug", "info"]).unwrap_err();
+        assert!(err
+            .to_string()
+            .ends_with("must be one of \"debug\", \"info\""));
+
+        assert!(one_of("WORKERS", &4u8, &[1, 2, 4, 8]).is_ok());
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn test_regex() {
+        assert!(regex("REGION", "us-east-1", r"^[a-z]+-[a-z]+-\d$").is_ok());
+        assert!(regex("REGION", "moon", r"^[a-z]+-[a-z]+-\d$").is_err());
+
+        let err = regex("REGION", "x", "(").unwrap_err();
+        assert!(err.to_string().contains("invalid pattern"));
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_required_if_ignores_unreadable_file() {
+        let var = EnvVar::new("TEST_REQUIRED_IF_BUCKET");
+        let other = EnvVar::new("TEST_REQUIRED_IF_STORAGE").from_file(true);
+        std::env::remove_var("TEST_REQUIRED_IF_BUCKET");
+        std::env::remove_var("TEST_REQUIRED_IF_STORAGE");
+
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        std::io::Write::write_all(&mut file, b"s3").unwrap();
+        std::env::set_var("TEST_REQUIRED_IF_STORAGE_FILE", file.path());
+        assert!(matches!(
+            required_if(&var, &other, "s3"),
+            Err(ServiceConfError::Constraint { .. })
+        ));
+
+        std::env::set_var("TEST_REQUIRED_IF_STORAGE_FILE", "/nonexistent/storage");
+        assert!(required_if(&var, &other, "s3").is_ok());
+        std::env::remove_var("TEST_REQUIRED_IF_STORAGE_FILE");
+    }
+}
@@ -1,7 +1,7 @@
 //! Integration tests
 
 use serial_test::serial;
-use serviceconf::ServiceConf;
+use serviceconf::{ServiceConf, ServiceConfSpec};
 use std::env;
 
 #[derive(Debug, ServiceConf)]
@@ -511,3 +511,1463 @@ fn test_deserializer_with_default() {
     env::remove_var("RETRY_INTERVAL");
     env::remove_var("DEFAULT_LIST");
 }
+
+#[derive(Debug, ServiceConf)]
+#[conf(prefix = "ALIAS_")]
+struct ConfigWithAliases {
+    #[conf(alias = "DB_URL", alias = "POSTGRES_URL")]
+    pub database_url: String,
+
+    #[conf(from_file, alias = "OLD_TOKEN")]
+    pub token: String,
+}
+
+#[test]
+#[serial]
+fn test_alias_fallback() {
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    let mut token_file = NamedTempFile::new().unwrap();
+    writeln!(token_file, "file_token").unwrap();
+
+    env::remove_var("ALIAS_DATABASE_URL");
+    env::remove_var("ALIAS_DB_URL");
+    env::set_var("ALIAS_POSTGRES_URL", "postgres://alias/db");
+    env::remove_var("ALIAS_TOKEN");
+    env::remove_var("ALIAS_TOKEN_FILE");
+    env::set_var("ALIAS_OLD_TOKEN_FILE", token_file.path());
+
+    let config = ConfigWithAliases::from_env().unwrap();
+    assert_eq!(config.database_url, "postgres://alias/db");
+    assert_eq!(config.token, "file_token");
+
+    // The primary name takes precedence over aliases
+    env::set_var("ALIAS_DATABASE_URL", "postgres://primary/db");
+    let config = ConfigWithAliases::from_env().unwrap();
+    assert_eq!(config.database_url, "postgres://primary/db");
+
+    env::remove_var("ALIAS_DATABASE_URL");
+    env::remove_var("ALIAS_POSTGRES_URL");
+    env::remove_var("ALIAS_OLD_TOKEN_FILE");
+}
+
+#[test]
+#[serial]
+fn test_alias_missing_error_lists_names() {
+    env::set_var("ALIAS_DATABASE_URL", "postgres://primary/db");
+    env::remove_var("ALIAS_TOKEN");
+    env::remove_var("ALIAS_TOKEN_FILE");
+    env::remove_var("ALIAS_OLD_TOKEN");
+    env::remove_var("ALIAS_OLD_TOKEN_FILE");
+
+    let err = ConfigWithAliases::from_env().unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Environment variable 'ALIAS_TOKEN' is required but not set \
+         (tried: ALIAS_TOKEN, ALIAS_TOKEN_FILE, ALIAS_OLD_TOKEN, ALIAS_OLD_TOKEN_FILE)"
+    );
+
+    env::remove_var("ALIAS_DATABASE_URL");
+}
+
+#[derive(Debug, ServiceConf)]
+struct ConfigWithDeprecatedName {
+    #[conf(alias = "LISTEN_PORT")]
+    #[conf(deprecated_name = "HTTP_PORT", note = "will be removed in v2")]
+    pub server_port: u16,
+}
+
+#[test]
+#[serial]
+fn test_deprecated_name_warns() {
+    use std::sync::{Arc, Mutex};
+
+    let warnings = Arc::new(Mutex::new(Vec::new()));
+    let captured = Arc::clone(&warnings);
+    serviceconf::set_deprecation_hook(move |usage| {
+        captured.lock().unwrap().push((
+            usage.name.to_string(),
+            usage.replacement.to_string(),
+            usage.to_string(),
+        ));
+    });
+
+    env::remove_var("SERVER_PORT");
+    env::remove_var("LISTEN_PORT");
+    env::set_var("HTTP_PORT", "8081");
+
+    let config = ConfigWithDeprecatedName::from_env().unwrap();
+    assert_eq!(config.server_port, 8081);
+    assert_eq!(
+        *warnings.lock().unwrap(),
+        vec![(
+            "HTTP_PORT".to_string(),
+            "SERVER_PORT".to_string(),
+            "Environment variable 'HTTP_PORT' is deprecated, use 'SERVER_PORT' instead: \
+             will be removed in v2"
+                .to_string(),
+        )]
+    );
+
+    // Current names and aliases do not warn
+    warnings.lock().unwrap().clear();
+    env::set_var("LISTEN_PORT", "8082");
+    let config = ConfigWithDeprecatedName::from_env().unwrap();
+    assert_eq!(config.server_port, 8082);
+    assert!(warnings.lock().unwrap().is_empty());
+
+    env::remove_var("LISTEN_PORT");
+    env::remove_var("HTTP_PORT");
+}
+
+#[derive(Debug, ServiceConf)]
+#[conf(prefix = "rename_", rename_all = "camelCase")]
+struct ConfigWithRenameAll {
+    pub database_url: String,
+
+    #[conf(name = "API_KEY")]
+    pub api_key: String,
+}
+
+#[test]
+#[serial]
+fn test_rename_all() {
+    env::set_var("rename_databaseUrl", "postgres://localhost/db");
+    env::set_var("rename_API_KEY", "secret");
+
+    let config = ConfigWithRenameAll::from_env().unwrap();
+    assert_eq!(config.database_url, "postgres://localhost/db");
+    assert_eq!(config.api_key, "secret");
+
+    env::remove_var("rename_databaseUrl");
+    env::remove_var("rename_API_KEY");
+}
+
+#[derive(Debug, ServiceConf)]
+struct ConfigWithRawIdentifiers {
+    pub r#type: String,
+
+    #[conf(default)]
+    pub r#ref: String,
+}
+
+#[test]
+#[serial]
+fn test_raw_identifier_names() {
+    env::set_var("TYPE", "primary");
+    env::set_var("REF", "main");
+
+    let config = ConfigWithRawIdentifiers::from_env().unwrap();
+    assert_eq!(config.r#type, "primary");
+    assert_eq!(config.r#ref, "main");
+
+    env::remove_var("TYPE");
+    env::remove_var("REF");
+}
+
+#[derive(Debug, ServiceConf)]
+struct ConfigWithDeserializerExpressions {
+    #[conf(deserializer = serde_json::from_str)]
+    pub tags: Vec<String>,
+
+    #[conf(deserializer = |s: &str| s.parse::<u64>().map(std::time::Duration::from_secs), default)]
+    pub grace_period: std::time::Duration,
+
+    #[conf(deserializer = comma_separated_deserializer)]
+    pub hosts: Option<Vec<String>>,
+}
+
+#[test]
+#[serial]
+fn test_deserializer_path_and_closure() {
+    env::set_var("TAGS", r#"["a","b"]"#);
+    env::set_var("GRACE_PERIOD", "15");
+    env::set_var("HOSTS", "a.example.com, b.example.com");
+
+    let config = ConfigWithDeserializerExpressions::from_env().unwrap();
+    assert_eq!(config.tags, vec!["a", "b"]);
+    assert_eq!(config.grace_period, std::time::Duration::from_secs(15));
+    assert_eq!(
+        config.hosts,
+        Some(vec![
+            "a.example.com".to_string(),
+            "b.example.com".to_string()
+        ])
+    );
+
+    env::remove_var("GRACE_PERIOD");
+    env::remove_var("HOSTS");
+    let config = ConfigWithDeserializerExpressions::from_env().unwrap();
+    assert_eq!(config.grace_period, std::time::Duration::ZERO);
+    assert_eq!(config.hosts, None);
+
+    env::remove_var("TAGS");
+}
+
+mod duration_secs {
+    use std::time::Duration;
+
+    pub fn parse(s: &str) -> Result<Duration, std::num::ParseIntError> {
+        s.parse().map(Duration::from_secs)
+    }
+
+    pub fn to_env_string(value: &Duration) -> String {
+        value.as_secs().to_string()
+    }
+}
+
+#[derive(Debug, ServiceConf)]
+struct ConfigWithModule {
+    #[conf(with = duration_secs)]
+    pub request_timeout: std::time::Duration,
+
+    #[conf(with = "duration_secs", default = std::time::Duration::from_secs(5))]
+    pub idle_timeout: std::time::Duration,
+
+    #[conf(with = duration_secs)]
+    pub max_lifetime: Option<std::time::Duration>,
+}
+
+#[test]
+#[serial]
+fn test_with_module() {
+    env::set_var("REQUEST_TIMEOUT", "30");
+    env::remove_var("IDLE_TIMEOUT");
+    env::set_var("MAX_LIFETIME", "3600");
+
+    let config = ConfigWithModule::from_env().unwrap();
+    assert_eq!(config.request_timeout, std::time::Duration::from_secs(30));
+    assert_eq!(config.idle_timeout, std::time::Duration::from_secs(5));
+    assert_eq!(
+        config.max_lifetime,
+        Some(std::time::Duration::from_secs(3600))
+    );
+    assert_eq!(duration_secs::to_env_string(&config.request_timeout), "30");
+
+    env::set_var("REQUEST_TIMEOUT", "soon");
+    let err = ConfigWithModule::from_env().unwrap_err();
+    assert!(err
+        .to_string()
+        .starts_with("Failed to parse environment variable 'REQUEST_TIMEOUT'"));
+
+    env::remove_var("REQUEST_TIMEOUT");
+    env::remove_var("MAX_LIFETIME");
+}
+
+#[derive(Debug, ServiceConf)]
+struct ConfigWithDefaultStr {
+    #[conf(default_str = "8080")]
+    pub listen_port: u16,
+
+    #[conf(deserializer = comma_separated_deserializer, default_str = "a, b")]
+    pub default_hosts: Vec<String>,
+
+    #[conf(with = duration_secs, default_str = "60")]
+    pub shutdown_timeout: std::time::Duration,
+}
+
+#[test]
+#[serial]
+fn test_default_str() {
+    env::remove_var("LISTEN_PORT");
+    env::remove_var("DEFAULT_HOSTS");
+    env::remove_var("SHUTDOWN_TIMEOUT");
+
+    let config = ConfigWithDefaultStr::from_env().unwrap();
+    assert_eq!(config.listen_port, 8080);
+    assert_eq!(config.default_hosts, vec!["a", "b"]);
+    assert_eq!(config.shutdown_timeout, std::time::Duration::from_secs(60));
+
+    env::set_var("LISTEN_PORT", "9090");
+    env::set_var("DEFAULT_HOSTS", "c");
+    env::set_var("SHUTDOWN_TIMEOUT", "5");
+
+    let config = ConfigWithDefaultStr::from_env().unwrap();
+    assert_eq!(config.listen_port, 9090);
+    assert_eq!(config.default_hosts, vec!["c"]);
+    assert_eq!(config.shutdown_timeout, std::time::Duration::from_secs(5));
+
+    env::remove_var("LISTEN_PORT");
+    env::remove_var("DEFAULT_HOSTS");
+    env::remove_var("SHUTDOWN_TIMEOUT");
+}
+
+#[test]
+#[serial]
+fn test_invalid_default_str_is_reported_when_set() {
+    // Declared inside a function: no items are generated outside the impl
+    #[derive(Debug, ServiceConf)]
+    #[conf(prefix = "BAD_DEFAULT_")]
+    #[allow(dead_code)]
+    struct Config {
+        #[conf(with = duration_secs, default_str = "1m")]
+        pub timeout: std::time::Duration,
+    }
+
+    env::remove_var("BAD_DEFAULT_TIMEOUT");
+    let err = Config::from_env().unwrap_err();
+    assert!(err.to_string().contains("invalid default value \"1m\""));
+
+    env::set_var("BAD_DEFAULT_TIMEOUT", "5");
+    let err = Config::from_env().unwrap_err();
+    assert!(matches!(err, serviceconf::ServiceConfError::Parse { .. }));
+    assert!(err.to_string().contains("invalid default value \"1m\""));
+    env::remove_var("BAD_DEFAULT_TIMEOUT");
+}
+
+fn next_port(port: &u16) -> u16 {
+    port + 1
+}
+
+fn default_cache_dir() -> String {
+    "/var/cache/app".to_string()
+}
+
+#[derive(Debug, ServiceConf)]
+#[conf(prefix = "COMPUTED_")]
+struct ConfigWithComputedDefaults {
+    #[conf(default_from = "port", default_fn = next_port)]
+    pub metrics_port: u16,
+
+    #[conf(default = 8080)]
+    pub port: u16,
+
+    #[conf(default_from = "port")]
+    pub admin_port: u16,
+
+    #[conf(default_fn = default_cache_dir)]
+    pub cache_dir: String,
+
+    #[conf(deserializer = comma_separated_deserializer, default_fn = Vec::new)]
+    pub peers: Vec<String>,
+}
+
+#[test]
+#[serial]
+fn test_default_fn_and_default_from() {
+    env::remove_var("COMPUTED_METRICS_PORT");
+    env::remove_var("COMPUTED_PORT");
+    env::remove_var("COMPUTED_ADMIN_PORT");
+    env::remove_var("COMPUTED_CACHE_DIR");
+    env::remove_var("COMPUTED_PEERS");
+
+    let config = ConfigWithComputedDefaults::from_env().unwrap();
+    assert_eq!(config.port, 8080);
+    assert_eq!(config.metrics_port, 8081);
+    assert_eq!(config.admin_port, 8080);
+    assert_eq!(config.cache_dir, "/var/cache/app");
+    assert!(config.peers.is_empty());
+
+    env::set_var("COMPUTED_PORT", "9000");
+    let config = ConfigWithComputedDefaults::from_env().unwrap();
+    assert_eq!(config.metrics_port, 9001);
+    assert_eq!(config.admin_port, 9000);
+
+    env::set_var("COMPUTED_METRICS_PORT", "9100");
+    env::set_var("COMPUTED_PEERS", "a,b");
+    let config = ConfigWithComputedDefaults::from_env().unwrap();
+    assert_eq!(config.metrics_port, 9100);
+    assert_eq!(config.peers, vec!["a", "b"]);
+
+    env::remove_var("COMPUTED_PORT");
+    env::remove_var("COMPUTED_METRICS_PORT");
+    env::remove_var("COMPUTED_PEERS");
+}
+
+#[derive(Debug, ServiceConf)]
+#[conf(prefix = "VALIDATED_")]
+struct ValidatedConfig {
+    #[conf(default = 8080, range(min = 1024, max = 65535))]
+    pub port: u16,
+
+    #[conf(non_empty)]
+    pub database_url: String,
+
+    #[conf(default_str = "info", one_of = ["debug", "info", "warn", "error"])]
+    pub log_level: String,
+
+    #[conf(deserializer = comma_separated_deserializer, min_len = 1, max_len = 3)]
+    pub hosts: Option<Vec<String>>,
+}
+
+#[test]
+#[serial]
+fn test_validation_attributes() {
+    env::remove_var("VALIDATED_PORT");
+    env::set_var("VALIDATED_DATABASE_URL", "postgres://localhost/app");
+    env::remove_var("VALIDATED_LOG_LEVEL");
+    env::remove_var("VALIDATED_HOSTS");
+
+    let config = ValidatedConfig::from_env().unwrap();
+    assert_eq!(config.port, 8080);
+    assert_eq!(config.database_url, "postgres://localhost/app");
+    assert_eq!(config.log_level, "info");
+    assert_eq!(config.hosts, None);
+
+    env::set_var("VALIDATED_PORT", "80");
+    let err = ValidatedConfig::from_env().unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Environment variable 'VALIDATED_PORT' failed range validation: must be between 1024 and 65535, got 80"
+    );
+    env::remove_var("VALIDATED_PORT");
+
+    env::set_var("VALIDATED_DATABASE_URL", "");
+    let err = ValidatedConfig::from_env().unwrap_err();
+    assert!(matches!(
+        err,
+        serviceconf::ServiceConfError::Validation { name, rule, .. }
+            if name == "VALIDATED_DATABASE_URL" && rule == "non_empty"
+    ));
+    env::set_var("VALIDATED_DATABASE_URL", "postgres://localhost/app");
+
+    env::set_var("VALIDATED_LOG_LEVEL", "verbose");
+    let err = ValidatedConfig::from_env().unwrap_err();
+    assert!(err.to_string().contains("failed one_of validation"));
+    env::remove_var("VALIDATED_LOG_LEVEL");
+
+    env::set_var("VALIDATED_HOSTS", "a,b,c,d");
+    let err = ValidatedConfig::from_env().unwrap_err();
+    assert!(err.to_string().contains("length must be at most 3, got 4"));
+
+    env::set_var("VALIDATED_HOSTS", "a,b");
+    let config = ValidatedConfig::from_env().unwrap();
+    assert_eq!(config.hosts, Some(vec!["a".to_string(), "b".to_string()]));
+
+    env::remove_var("VALIDATED_DATABASE_URL");
+    env::remove_var("VALIDATED_HOSTS");
+}
+
+fn check_pin(pin: &u32) -> Result<(), String> {
+    if pin % 10 == 0 {
+        return Err(format!("{} must not end in 0", pin));
+    }
+    Ok(())
+}
+
+#[derive(Debug, ServiceConf)]
+#[conf(prefix = "VALIDATED_SECRET_")]
+#[allow(dead_code)]
+struct ValidatedSecretConfig {
+    #[conf(secret, range(min = 100000), validate = check_pin)]
+    pub pin: u32,
+}
+
+#[test]
+#[serial]
+fn test_validation_hides_secret_values() {
+    env::set_var("VALIDATED_SECRET_PIN", "4321");
+    let err = ValidatedSecretConfig::from_env().unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Environment variable 'VALIDATED_SECRET_PIN' failed range validation: must be at least 100000"
+    );
+
+    env::set_var("VALIDATED_SECRET_PIN", "987650");
+    let err = ValidatedSecretConfig::from_env().unwrap_err();
+    assert!(matches!(
+        err,
+        serviceconf::ServiceConfError::Validation { ref rule, .. } if rule == "check_pin"
+    ));
+    assert!(!err.to_string().contains("987650"));
+
+    env::remove_var("VALIDATED_SECRET_PIN");
+}
+
+#[cfg(feature = "regex")]
+#[derive(Debug, ServiceConf)]
+struct RegexValidatedConfig {
+    #[conf(name = "VALIDATED_REGION", regex = r"^[a-z]+-[a-z]+-\d+$")]
+    pub region: String,
+}
+
+#[cfg(feature = "regex")]
+#[test]
+#[serial]
+fn test_regex_validation() {
+    env::set_var("VALIDATED_REGION", "us-east-1");
+    assert_eq!(
+        RegexValidatedConfig::from_env().unwrap().region,
+        "us-east-1"
+    );
+
+    env::set_var("VALIDATED_REGION", "moon");
+    let err = RegexValidatedConfig::from_env().unwrap_err();
+    assert!(err.to_string().contains("failed regex validation"));
+
+    env::remove_var("VALIDATED_REGION");
+}
+
+mod pool_checks {
+    pub fn not_zero(value: &u32) -> Result<(), String> {
+        if *value == 0 {
+            return Err("must not be zero".to_string());
+        }
+        Ok(())
+    }
+
+    pub fn min_not_above_max(config: &super::PoolConfig) -> Result<(), String> {
+        if config.min_connections > config.max_connections {
+            return Err(format!(
+                "POOL_MIN_CONNECTIONS ({}) must not exceed POOL_MAX_CONNECTIONS ({})",
+                config.min_connections, config.max_connections
+            ));
+        }
+        Ok(())
+    }
+}
+
+fn tls_pair(config: &PoolConfig) -> anyhow::Result<()> {
+    if config.tls_cert.is_some() != config.tls_key.is_some() {
+        anyhow::bail!("POOL_TLS_CERT and POOL_TLS_KEY must be set together");
+    }
+    Ok(())
+}
+
+#[derive(Debug, ServiceConf)]
+#[conf(prefix = "POOL_")]
+#[conf(validate = pool_checks::min_not_above_max, validate = tls_pair)]
+struct PoolConfig {
+    #[conf(default = 1)]
+    pub min_connections: u32,
+
+    #[conf(default = 10, validate = pool_checks::not_zero)]
+    pub max_connections: u32,
+
+    pub tls_cert: Option<String>,
+
+    pub tls_key: Option<String>,
+}
+
+#[test]
+#[serial]
+fn test_custom_validators() {
+    env::remove_var("POOL_MIN_CONNECTIONS");
+    env::remove_var("POOL_MAX_CONNECTIONS");
+    env::remove_var("POOL_TLS_CERT");
+    env::remove_var("POOL_TLS_KEY");
+
+    let config = PoolConfig::from_env().unwrap();
+    assert_eq!(config.min_connections, 1);
+    assert_eq!(config.max_connections, 10);
+
+    env::set_var("POOL_MAX_CONNECTIONS", "0");
+    let err = PoolConfig::from_env().unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Environment variable 'POOL_MAX_CONNECTIONS' failed not_zero validation: must not be zero"
+    );
+
+    env::set_var("POOL_MIN_CONNECTIONS", "5");
+    env::set_var("POOL_MAX_CONNECTIONS", "2");
+    let err = PoolConfig::from_env().unwrap_err();
+    assert!(matches!(
+        err,
+        serviceconf::ServiceConfError::InvalidConfig { type_name, message }
+            if type_name.ends_with("PoolConfig")
+                && message == "POOL_MIN_CONNECTIONS (5) must not exceed POOL_MAX_CONNECTIONS (2)"
+    ));
+    env::remove_var("POOL_MIN_CONNECTIONS");
+    env::remove_var("POOL_MAX_CONNECTIONS");
+
+    env::set_var("POOL_TLS_CERT", "/etc/tls/cert.pem");
+    let err = PoolConfig::from_env().unwrap_err();
+    assert!(err
+        .to_string()
+        .ends_with("POOL_TLS_CERT and POOL_TLS_KEY must be set together"));
+
+    env::set_var("POOL_TLS_KEY", "/etc/tls/key.pem");
+    let config = PoolConfig::from_env().unwrap();
+    assert_eq!(config.tls_key.as_deref(), Some("/etc/tls/key.pem"));
+
+    env::remove_var("POOL_TLS_CERT");
+    env::remove_var("POOL_TLS_KEY");
+}
+
+#[derive(Debug, ServiceConf)]
+#[conf(prefix = "CONSTRAINED_")]
+struct ConstrainedConfig {
+    #[conf(requires = "tls_key")]
+    pub tls_cert: Option<String>,
+
+    pub tls_key: Option<String>,
+
+    #[conf(conflicts_with = "token")]
+    pub password: Option<String>,
+
+    #[conf(from_file)]
+    pub token: Option<String>,
+
+    #[conf(default_str = "local")]
+    pub storage: String,
+
+    #[conf(required_if(storage = "s3"))]
+    pub s3_bucket: Option<String>,
+}
+
+#[test]
+#[serial]
+fn test_cross_field_constraints() {
+    for name in [
+        "CONSTRAINED_TLS_CERT",
+        "CONSTRAINED_TLS_KEY",
+        "CONSTRAINED_PASSWORD",
+        "CONSTRAINED_TOKEN",
+        "CONSTRAINED_TOKEN_FILE",
+        "CONSTRAINED_STORAGE",
+        "CONSTRAINED_S3_BUCKET",
+    ] {
+        env::remove_var(name);
+    }
+
+    let config = ConstrainedConfig::from_env().unwrap();
+    assert_eq!(config.storage, "local");
+
+    env::set_var("CONSTRAINED_TLS_CERT", "cert.pem");
+    let err = ConstrainedConfig::from_env().unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Environment variable 'CONSTRAINED_TLS_CERT' requires 'CONSTRAINED_TLS_KEY' to be set"
+    );
+    env::set_var("CONSTRAINED_TLS_KEY", "key.pem");
+    assert!(ConstrainedConfig::from_env().is_ok());
+
+    // Presence via the `_FILE` variant counts; the unreadable file is reported too
+    env::set_var("CONSTRAINED_PASSWORD", "hunter2");
+    env::set_var("CONSTRAINED_TOKEN_FILE", "/nonexistent/token");
+    let err = ConstrainedConfig::from_env().unwrap_err();
+    let errors = err.errors();
+    assert_eq!(errors.len(), 2);
+    assert!(matches!(
+        errors[1],
+        serviceconf::ServiceConfError::FileRead { .. }
+    ));
+    assert!(matches!(
+        &errors[0],
+        serviceconf::ServiceConfError::Constraint {
+            name,
+            other,
+            constraint: serviceconf::Constraint::ConflictsWith,
+        } if name == "CONSTRAINED_PASSWORD" && other == "CONSTRAINED_TOKEN"
+    ));
+    env::remove_var("CONSTRAINED_TOKEN_FILE");
+
+    env::set_var("CONSTRAINED_STORAGE", "s3");
+    let err = ConstrainedConfig::from_env().unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Environment variable 'CONSTRAINED_S3_BUCKET' is required when 'CONSTRAINED_STORAGE' is 's3'"
+    );
+    env::set_var("CONSTRAINED_S3_BUCKET", "assets");
+    let config = ConstrainedConfig::from_env().unwrap();
+    assert_eq!(config.s3_bucket.as_deref(), Some("assets"));
+    assert_eq!(config.password.as_deref(), Some("hunter2"));
+    assert_eq!(config.tls_cert.as_deref(), Some("cert.pem"));
+    assert_eq!(config.tls_key.as_deref(), Some("key.pem"));
+    assert_eq!(config.token, None);
+
+    for name in [
+        "CONSTRAINED_TLS_CERT",
+        "CONSTRAINED_TLS_KEY",
+        "CONSTRAINED_PASSWORD",
+        "CONSTRAINED_STORAGE",
+        "CONSTRAINED_S3_BUCKET",
+    ] {
+        env::remove_var(name);
+    }
+}
+
+#[derive(Debug, ServiceConf)]
+#[conf(prefix = "FILE_CONSTRAINED_")]
+#[allow(dead_code)]
+struct FileConstrainedConfig {
+    #[conf(from_file, default_str = "local")]
+    pub storage: String,
+
+    #[conf(required_if(storage = "s3"))]
+    pub s3_bucket: Option<String>,
+}
+
+#[test]
+#[serial]
+fn test_required_if_reports_unreadable_file_once() {
+    env::remove_var("FILE_CONSTRAINED_STORAGE");
+    env::remove_var("FILE_CONSTRAINED_S3_BUCKET");
+    env::set_var("FILE_CONSTRAINED_STORAGE_FILE", "/nonexistent/storage");
+
+    let err = FileConstrainedConfig::from_env().unwrap_err();
+    assert!(matches!(
+        err,
+        serviceconf::ServiceConfError::FileRead { .. }
+    ));
+
+    env::remove_var("FILE_CONSTRAINED_STORAGE_FILE");
+}
+
+#[derive(Debug, ServiceConf)]
+#[conf(prefix = "ACCUMULATE_")]
+struct AccumulatingConfig {
+    pub database_url: String,
+
+    pub port: u16,
+
+    #[conf(default_from = "port")]
+    pub admin_port: u16,
+
+    #[conf(non_empty)]
+    pub api_key: String,
+}
+
+#[derive(Debug, ServiceConf)]
+#[conf(prefix = "ACCUMULATE_", fail_fast)]
+struct FailFastConfig {
+    pub database_url: String,
+
+    pub port: u16,
+
+    #[conf(default_from = "port", default_fn = next_port)]
+    pub admin_port: u16,
+}
+
+#[test]
+#[serial]
+fn test_errors_are_accumulated() {
+    env::remove_var("ACCUMULATE_DATABASE_URL");
+    env::set_var("ACCUMULATE_PORT", "http");
+    env::remove_var("ACCUMULATE_ADMIN_PORT");
+    env::set_var("ACCUMULATE_API_KEY", "");
+
+    let err = AccumulatingConfig::from_env().unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "3 configuration errors:\n  \
+         - Environment variable 'ACCUMULATE_DATABASE_URL' is required but not set\n  \
+         - Failed to parse environment variable 'ACCUMULATE_PORT' as u16: invalid digit found in string\n  \
+         - Environment variable 'ACCUMULATE_API_KEY' failed non_empty validation: must not be empty"
+    );
+
+    // A set variable is still loaded when the field its default depends on failed
+    env::set_var("ACCUMULATE_ADMIN_PORT", "admin");
+    let err = AccumulatingConfig::from_env().unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "4 configuration errors:\n  \
+         - Environment variable 'ACCUMULATE_DATABASE_URL' is required but not set\n  \
+         - Failed to parse environment variable 'ACCUMULATE_PORT' as u16: invalid digit found in string\n  \
+         - Failed to parse environment variable 'ACCUMULATE_ADMIN_PORT' as u16: invalid digit found in string\n  \
+         - Environment variable 'ACCUMULATE_API_KEY' failed non_empty validation: must not be empty"
+    );
+
+    env::set_var("ACCUMULATE_ADMIN_PORT", "9090");
+    let err = AccumulatingConfig::from_env().unwrap_err();
+    assert_eq!(err.errors().len(), 3);
+    env::remove_var("ACCUMULATE_ADMIN_PORT");
+
+    let err = FailFastConfig::from_env().unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Environment variable 'ACCUMULATE_DATABASE_URL' is required but not set"
+    );
+
+    env::set_var("ACCUMULATE_DATABASE_URL", "postgres://localhost/app");
+    env::set_var("ACCUMULATE_PORT", "8080");
+    env::set_var("ACCUMULATE_API_KEY", "secret");
+
+    let config = AccumulatingConfig::from_env().unwrap();
+    assert_eq!(config.database_url, "postgres://localhost/app");
+    assert_eq!(config.port, 8080);
+    assert_eq!(config.admin_port, 8080);
+    assert_eq!(config.api_key, "secret");
+
+    let config = FailFastConfig::from_env().unwrap();
+    assert_eq!(config.database_url, "postgres://localhost/app");
+    assert_eq!(config.port, 8080);
+    assert_eq!(config.admin_port, 8081);
+
+    env::remove_var("ACCUMULATE_DATABASE_URL");
+    env::remove_var("ACCUMULATE_PORT");
+    env::remove_var("ACCUMULATE_API_KEY");
+}
+
+#[derive(Debug)]
+enum AppError {
+    Config(serviceconf::ServiceConfError),
+}
+
+impl From<serviceconf::ServiceConfError> for AppError {
+    fn from(e: serviceconf::ServiceConfError) -> Self {
+        AppError::Config(e)
+    }
+}
+
+#[derive(Debug, ServiceConf)]
+#[conf(prefix = "TYPED_", error = AppError)]
+struct ConfigWithAppError {
+    pub database_url: String,
+}
+
+#[test]
+#[serial]
+fn test_typed_errors() {
+    env::remove_var("TYPED_DATABASE_URL");
+
+    let err = ConfigWithAppError::from_env().unwrap_err();
+    let AppError::Config(err) = err;
+    assert!(matches!(
+        err,
+        serviceconf::ServiceConfError::Missing { ref name, .. } if name == "TYPED_DATABASE_URL"
+    ));
+
+    env::set_var("TYPED_DATABASE_URL", "postgres://localhost/app");
+    let config = ConfigWithAppError::from_env().unwrap();
+    assert_eq!(config.database_url, "postgres://localhost/app");
+    env::remove_var("TYPED_DATABASE_URL");
+}
+
+#[cfg(feature = "anyhow")]
+#[derive(Debug, ServiceConf)]
+#[conf(prefix = "TYPED_", error = serviceconf::anyhow::Error)]
+struct ConfigWithAnyhowError {
+    pub database_url: String,
+}
+
+#[cfg(feature = "anyhow")]
+#[test]
+#[serial]
+fn test_anyhow_error() {
+    env::remove_var("TYPED_DATABASE_URL");
+
+    let err: serviceconf::anyhow::Error = ConfigWithAnyhowError::from_env().unwrap_err();
+    assert!(err
+        .downcast_ref::<serviceconf::ServiceConfError>()
+        .is_some());
+
+    env::set_var("TYPED_DATABASE_URL", "postgres://localhost/app");
+    let config = ConfigWithAnyhowError::from_env().unwrap();
+    assert_eq!(config.database_url, "postgres://localhost/app");
+    env::remove_var("TYPED_DATABASE_URL");
+}
+
+#[derive(Debug, ServiceConf)]
+#[conf(prefix = "SKIPPED_")]
+struct ConfigWithSkippedFields {
+    pub database_url: String,
+
+    #[conf(skip)]
+    pub client: Option<std::sync::Arc<String>>,
+
+    #[conf(skip, default = std::sync::Arc::new(vec![1, 2, 3]))]
+    pub shards: std::sync::Arc<Vec<u8>>,
+
+    #[conf(skip)]
+    pub marker: std::marker::PhantomData<u8>,
+}
+
+#[test]
+#[serial]
+fn test_skipped_fields() {
+    env::set_var("SKIPPED_DATABASE_URL", "postgres://localhost/app");
+    // Skipped fields are never read, even when a matching variable is set
+    env::set_var("SKIPPED_CLIENT", "ignored");
+
+    let config = ConfigWithSkippedFields::from_env().unwrap();
+    assert_eq!(config.database_url, "postgres://localhost/app");
+    assert!(config.client.is_none());
+    assert_eq!(*config.shards, vec![1, 2, 3]);
+    assert_eq!(config.marker, std::marker::PhantomData);
+
+    env::remove_var("SKIPPED_DATABASE_URL");
+    env::remove_var("SKIPPED_CLIENT");
+}
+
+#[derive(ServiceConf)]
+#[conf(prefix = "REDACTED_", debug, display)]
+struct ConfigWithSecrets {
+    pub host: String,
+
+    #[conf(from_file)]
+    pub api_key: String,
+
+    #[conf(secret)]
+    pub password: Option<String>,
+
+    #[conf(from_file, secret = false)]
+    pub ca_cert: String,
+
+    pub proxy: Option<String>,
+
+    #[conf(skip)]
+    pub client: std::marker::PhantomData<u8>,
+}
+
+#[test]
+#[serial]
+fn test_redacted_debug_and_display() {
+    env::set_var("REDACTED_HOST", "localhost");
+    env::set_var("REDACTED_API_KEY", "key-123");
+    env::set_var("REDACTED_PASSWORD", "hunter2");
+    env::set_var("REDACTED_CA_CERT", "cert");
+    env::remove_var("REDACTED_PROXY");
+
+    let config = ConfigWithSecrets::from_env().unwrap();
+    assert_eq!(config.api_key, "key-123");
+    assert_eq!(config.password.as_deref(), Some("hunter2"));
+    assert_eq!(config.client, std::marker::PhantomData);
+
+    assert_eq!(
+        format!("{:?}", config),
+        r#"ConfigWithSecrets { host: "localhost", api_key: ***, password: ***, ca_cert: "cert", proxy: None, .. }"#
+    );
+    assert_eq!(
+        config.to_string(),
+        "REDACTED_HOST=\"localhost\"\n\
+         REDACTED_API_KEY=***\n\
+         REDACTED_PASSWORD=***\n\
+         REDACTED_CA_CERT=\"cert\"\n\
+         REDACTED_PROXY=<unset>"
+    );
+
+    env::remove_var("REDACTED_HOST");
+    env::remove_var("REDACTED_API_KEY");
+    env::remove_var("REDACTED_PASSWORD");
+    env::remove_var("REDACTED_CA_CERT");
+}
+
+#[derive(Debug, ServiceConf)]
+#[conf(prefix = "WRAPPED_")]
+struct ConfigWithSecretType {
+    #[conf(from_file)]
+    pub api_key: serviceconf::Secret<String>,
+
+    pub token: Option<serviceconf::Secret<String>>,
+
+    #[conf(default_str = "1234")]
+    pub pin: serviceconf::Secret<u32>,
+
+    #[conf(default = serviceconf::Secret::new("guest".to_string()))]
+    pub password: serviceconf::Secret<String>,
+
+    #[conf(deserializer = serde_json::from_str)]
+    pub recovery_codes: serviceconf::Secret<Vec<String>>,
+
+    #[conf(deserializer = serde_json::from_str)]
+    pub backup_codes: Option<serviceconf::Secret<Vec<String>>>,
+}
+
+#[test]
+#[serial]
+fn test_secret_type() {
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    let mut temp_file = NamedTempFile::new().unwrap();
+    writeln!(temp_file, "key-from-file").unwrap();
+
+    env::remove_var("WRAPPED_API_KEY");
+    env::set_var("WRAPPED_API_KEY_FILE", temp_file.path());
+    env::remove_var("WRAPPED_TOKEN");
+    env::remove_var("WRAPPED_PIN");
+    env::remove_var("WRAPPED_PASSWORD");
+    env::set_var("WRAPPED_RECOVERY_CODES", r#"["a1","b2"]"#);
+    env::remove_var("WRAPPED_BACKUP_CODES");
+
+    let config = ConfigWithSecretType::from_env().unwrap();
+    assert_eq!(config.api_key.expose(), "key-from-file");
+    assert!(config.token.is_none());
+    assert_eq!(*config.pin.expose(), 1234);
+    assert_eq!(config.password.expose(), "guest");
+    assert_eq!(config.recovery_codes.expose(), &["a1", "b2"]);
+    assert!(config.backup_codes.is_none());
+    assert_eq!(
+        format!("{:?}", config),
+        "ConfigWithSecretType { api_key: ***, token: None, pin: ***, password: ***, \
+         recovery_codes: ***, backup_codes: None }"
+    );
+
+    env::set_var("WRAPPED_TOKEN", "token-123");
+    env::set_var("WRAPPED_BACKUP_CODES", r#"["c3"]"#);
+    let config = ConfigWithSecretType::from_env().unwrap();
+    assert_eq!(config.token.as_ref().unwrap().expose(), "token-123");
+    assert_eq!(config.backup_codes.unwrap().expose(), &["c3"]);
+
+    env::remove_var("WRAPPED_API_KEY_FILE");
+    env::remove_var("WRAPPED_TOKEN");
+    env::remove_var("WRAPPED_RECOVERY_CODES");
+    env::remove_var("WRAPPED_BACKUP_CODES");
+}
+
+mod imported_secret {
+    use serviceconf::{Secret, ServiceConf};
+
+    pub type SessionKey = Secret<String>;
+
+    #[derive(ServiceConf)]
+    #[conf(prefix = "IMPORTED_", debug, display)]
+    pub struct Config {
+        pub pin: Secret<u32>,
+
+        pub session_key: SessionKey,
+
+        #[conf(deserializer = serde_json::from_str)]
+        pub codes: Option<Secret<Vec<u32>>>,
+    }
+}
+
+#[test]
+#[serial]
+fn test_imported_secret_type() {
+    use imported_secret::Config;
+
+    assert!(Config::env_vars().iter().all(|spec| spec.secret));
+    let env = Config::kubernetes_env("app-secrets");
+    assert!(env.contains("  - name: IMPORTED_PIN\n    valueFrom:\n      secretKeyRef:\n"));
+
+    env::set_var("IMPORTED_PIN", "12x4");
+    env::set_var("IMPORTED_SESSION_KEY", "session-abc123");
+    env::set_var("IMPORTED_CODES", r#"[1, "code-xyz789"]"#);
+    let err = Config::from_env().unwrap_err();
+    let message = err.to_string();
+    assert!(!message.contains("12x4"), "{}", message);
+    assert!(!message.contains("invalid digit"), "{}", message);
+    assert!(!message.contains("xyz789"), "{}", message);
+    assert_eq!(err.errors().len(), 2);
+    assert!(err.errors().iter().all(|e| matches!(
+        e,
+        serviceconf::ServiceConfError::Parse { message, .. }
+            if message == "invalid value (details hidden for secret field)"
+    )));
+
+    env::set_var("IMPORTED_PIN", "1234");
+    env::set_var("IMPORTED_CODES", "[1, 2]");
+    let config = Config::from_env().unwrap();
+    assert_eq!(*config.pin.expose(), 1234);
+    assert_eq!(config.session_key.expose(), "session-abc123");
+    assert_eq!(config.codes.as_ref().unwrap().expose(), &[1, 2]);
+    assert_eq!(
+        format!("{:?}", config),
+        "Config { pin: ***, session_key: ***, codes: *** }"
+    );
+    assert_eq!(
+        config.to_string(),
+        "IMPORTED_PIN=***\nIMPORTED_SESSION_KEY=***\nIMPORTED_CODES=***"
+    );
+
+    env::remove_var("IMPORTED_PIN");
+    env::remove_var("IMPORTED_SESSION_KEY");
+    env::remove_var("IMPORTED_CODES");
+}
+
+fn parse_level(s: &str) -> Result<u8, String> {
+    match s {
+        "low" => Ok(1),
+        "high" => Ok(2),
+        other => Err(format!("unknown level '{}'", other)),
+    }
+}
+
+#[derive(Debug, ServiceConf)]
+#[conf(prefix = "SCRUBBED_", show_values)]
+struct ConfigWithScrubbedErrors {
+    #[conf(from_file, deserializer = parse_level)]
+    pub key_level: u8,
+
+    #[conf(deserializer = parse_level)]
+    pub token_level: serviceconf::Secret<u8>,
+
+    #[conf(deserializer = parse_level)]
+    pub log_level: u8,
+}
+
+#[test]
+#[serial]
+fn test_parse_errors_scrub_secrets() {
+    env::set_var("SCRUBBED_KEY_LEVEL", "key-abc123");
+    env::set_var("SCRUBBED_TOKEN_LEVEL", "token-xyz789");
+    env::set_var("SCRUBBED_LOG_LEVEL", "verbose");
+
+    let err = ConfigWithScrubbedErrors::from_env().unwrap_err();
+    let message = err.to_string();
+    assert!(!message.contains("abc123"), "{}", message);
+    assert!(!message.contains("xyz789"), "{}", message);
+    assert_eq!(
+        err.errors()
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>(),
+        [
+            "Failed to parse environment variable 'SCRUBBED_KEY_LEVEL' as u8: \
+             invalid value (details hidden for secret field)",
+            "Failed to parse environment variable 'SCRUBBED_TOKEN_LEVEL' as serviceconf::secret::Secret<u8>: \
+             invalid value (details hidden for secret field)",
+            "Failed to parse environment variable 'SCRUBBED_LOG_LEVEL' as u8: \
+             unknown level 'verbose' (value: \"verbose\")",
+        ]
+    );
+
+    env::set_var("SCRUBBED_KEY_LEVEL", "low");
+    env::set_var("SCRUBBED_TOKEN_LEVEL", "high");
+    env::set_var("SCRUBBED_LOG_LEVEL", "low");
+    let config = ConfigWithScrubbedErrors::from_env().unwrap();
+    assert_eq!(config.key_level, 1);
+    assert_eq!(*config.token_level.expose(), 2);
+    assert_eq!(config.log_level, 1);
+
+    env::remove_var("SCRUBBED_KEY_LEVEL");
+    env::remove_var("SCRUBBED_TOKEN_LEVEL");
+    env::remove_var("SCRUBBED_LOG_LEVEL");
+}
+
+#[derive(Debug, ServiceConf)]
+#[conf(prefix = "ORIGIN_")]
+struct ConfigWithProvenance {
+    #[conf(alias = "HTTP_PORT", default = 8080)]
+    pub port: u16,
+
+    #[conf(from_file)]
+    pub api_key: String,
+
+    #[conf(default_str = "info")]
+    pub log_level: String,
+
+    pub proxy: Option<String>,
+
+    pub database_url: String,
+
+    #[conf(skip)]
+    pub client: Option<String>,
+}
+
+#[test]
+#[serial]
+fn test_provenance() {
+    use serviceconf::{NameKind, Source};
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    let mut temp_file = NamedTempFile::new().unwrap();
+    writeln!(temp_file, "key-from-file").unwrap();
+    let path = temp_file.path().to_str().unwrap().to_string();
+
+    env::remove_var("ORIGIN_PORT");
+    env::set_var("ORIGIN_HTTP_PORT", "9090");
+    env::remove_var("ORIGIN_API_KEY");
+    env::set_var("ORIGIN_API_KEY_FILE", &path);
+    env::remove_var("ORIGIN_LOG_LEVEL");
+    env::remove_var("ORIGIN_PROXY");
+    env::set_var("ORIGIN_DATABASE_URL", "postgres://localhost/app");
+
+    let (config, provenance) = ConfigWithProvenance::from_env_with_provenance().unwrap();
+    assert_eq!(config.port, 9090);
+    assert_eq!(config.api_key, "key-from-file");
+    assert_eq!(config.log_level, "info");
+    assert!(config.proxy.is_none());
+    assert_eq!(config.database_url, "postgres://localhost/app");
+    assert!(config.client.is_none());
+
+    let port = provenance.get("port").unwrap();
+    assert_eq!(port.env_var, "ORIGIN_PORT");
+    assert_eq!(
+        port.source,
+        Source::Env {
+            name: "ORIGIN_HTTP_PORT".to_string(),
+            kind: NameKind::Alias,
+        }
+    );
+    assert_eq!(
+        provenance.get("api_key").unwrap().source,
+        Source::File {
+            name: "ORIGIN_API_KEY_FILE".to_string(),
+            path: path.clone(),
+            kind: NameKind::Primary,
+        }
+    );
+    assert_eq!(
+        provenance.get("log_level").unwrap().source,
+        Source::Default {
+            expr: "info".to_string()
+        }
+    );
+    assert_eq!(provenance.get("proxy").unwrap().source, Source::Unset);
+    assert!(provenance.get("client").is_none());
+    assert_eq!(provenance.iter().count(), 5);
+    assert_eq!(
+        provenance.to_string(),
+        format!(
+            "port: env ORIGIN_HTTP_PORT (alias)\n\
+             api_key: file {} (via ORIGIN_API_KEY_FILE)\n\
+             log_level: default info\n\
+             proxy: unset\n\
+             database_url: env ORIGIN_DATABASE_URL",
+            path
+        )
+    );
+
+    // Errors are the same as from_env()
+    env::remove_var("ORIGIN_DATABASE_URL");
+    assert!(matches!(
+        ConfigWithProvenance::from_env_with_provenance(),
+        Err(serviceconf::ServiceConfError::Missing { .. })
+    ));
+
+    env::remove_var("ORIGIN_HTTP_PORT");
+    env::remove_var("ORIGIN_API_KEY_FILE");
+}
+
+#[derive(ServiceConf)]
+#[conf(prefix = "SPEC_", name_consts)]
+#[allow(dead_code)]
+struct ConfigWithSpecs {
+    /// Port the HTTP server listens on
+    #[conf(
+        alias = "HTTP_PORT",
+        deprecated_name = "SERVER_PORT",
+        default = 8080,
+        range(min = 1024),
+        group = "Server"
+    )]
+    pub port: u16,
+
+    /// API key for the upstream service
+    ///
+    /// Mount it as a file in production.
+    #[conf(from_file)]
+    pub api_key: String,
+
+    #[conf(deserializer = "comma_separated_deserializer")]
+    pub allowed_hosts: Option<Vec<String>>,
+
+    #[conf(name = "DB_URL", group = "Database")]
+    pub database_url: String,
+
+    #[conf(skip)]
+    pub client: Option<String>,
+}
+
+#[test]
+fn test_env_vars() {
+    use serviceconf::{EnvVarSpec, Validation};
+
+    assert_eq!(ConfigWithSpecs::PORT, "SPEC_PORT");
+    assert_eq!(ConfigWithSpecs::API_KEY, "SPEC_API_KEY");
+    assert_eq!(ConfigWithSpecs::DATABASE_URL, "SPEC_DB_URL");
+
+    let specs = ConfigWithSpecs::env_vars();
+    assert_eq!(specs.len(), 4);
+    assert_eq!(
+        specs[0],
+        EnvVarSpec {
+            field: "port",
+            name: "SPEC_PORT",
+            aliases: &["SPEC_HTTP_PORT"],
+            deprecated_names: &["SPEC_SERVER_PORT"],
+            type_name: "u16",
+            required: false,
+            default: Some("8080"),
+            env_default: Some("8080"),
+            env_default_fn: None,
+            from_file: false,
+            validations: &[Validation::Range {
+                min: Some("1024"),
+                max: None,
+            }],
+            deserializer: None,
+            secret: false,
+            doc: "Port the HTTP server listens on",
+            group: Some("Server"),
+        }
+    );
+    assert_eq!(
+        specs[0].names().collect::<Vec<_>>(),
+        ["SPEC_PORT", "SPEC_HTTP_PORT", "SPEC_SERVER_PORT"]
+    );
+
+    let api_key = &specs[1];
+    assert!(api_key.required);
+    assert!(api_key.from_file);
+    assert!(api_key.secret);
+    assert_eq!(
+        api_key.doc,
+        "API key for the upstream service\n\nMount it as a file in production."
+    );
+
+    let allowed_hosts = &specs[2];
+    assert_eq!(allowed_hosts.type_name, "Option<Vec<String>>");
+    assert!(!allowed_hosts.required);
+    assert_eq!(allowed_hosts.default, None);
+    assert_eq!(
+        allowed_hosts.deserializer,
+        Some("comma_separated_deserializer")
+    );
+    assert_eq!(allowed_hosts.doc, "");
+
+    assert_eq!(specs[3].name, ConfigWithSpecs::DATABASE_URL);
+    assert!(specs[3].required);
+    assert!(specs.iter().all(|spec| spec.field != "client"));
+}
+
+#[test]
+fn test_env_example() {
+    assert_eq!(
+        ConfigWithSpecs::env_example(),
+        "# Port the HTTP server listens on\n\
+         # SPEC_PORT=8080\n\
+         \n\
+         # API key for the upstream service\n\
+         #\n\
+         # Mount it as a file in production.\n\
+         # required\n\
+         # Or set SPEC_API_KEY_FILE to the path of a file containing the value\n\
+         SPEC_API_KEY=\n\
+         \n\
+         # SPEC_ALLOWED_HOSTS=\n\
+         \n\
+         # required\n\
+         SPEC_DB_URL=\n"
+    );
+}
+
+#[derive(ServiceConf)]
+#[conf(prefix = "EXPR_")]
+#[allow(dead_code)]
+struct ConfigWithExpressionDefaults {
+    #[conf(deserializer = parse_duration_secs, default = std::time::Duration::from_secs(60))]
+    pub timeout: std::time::Duration,
+
+    #[conf(default = "localhost".to_string())]
+    pub host: String,
+
+    #[conf(default)]
+    pub workers: u32,
+
+    #[conf(default_str = "30", deserializer = parse_duration_secs)]
+    pub grace_period: std::time::Duration,
+
+    #[conf(with = duration_secs, default = std::time::Duration::from_secs(90))]
+    pub idle_timeout: std::time::Duration,
+}
+
+#[test]
+fn test_env_example_expression_defaults() {
+    let specs = ConfigWithExpressionDefaults::env_vars();
+    assert_eq!(specs[0].default, Some("std::time::Duration::from_secs(60)"));
+    assert_eq!(specs[0].env_default, None);
+    assert_eq!(specs[3].env_default, Some("30"));
+    assert_eq!(specs[4].env_default, None);
+    assert_eq!(specs[4].env_default_value().as_deref(), Some("90"));
+
+    assert_eq!(
+        ConfigWithExpressionDefaults::env_example(),
+        "# default: std::time::Duration::from_secs(60)\n\
+         # EXPR_TIMEOUT=\n\
+         \n\
+         # default: \"localhost\".to_string()\n\
+         # EXPR_HOST=\n\
+         \n\
+         # default: Default::default()\n\
+         # EXPR_WORKERS=\n\
+         \n\
+         # EXPR_GRACE_PERIOD=30\n\
+         \n\
+         # EXPR_IDLE_TIMEOUT=90\n"
+    );
+
+    let compose = ConfigWithExpressionDefaults::compose_env();
+    assert_eq!(
+        compose,
+        "environment: {}\n\
+         \x20 # default: std::time::Duration::from_secs(60)\n\
+         \x20 # EXPR_TIMEOUT: \"\"\n\
+         \x20 # default: \"localhost\".to_string()\n\
+         \x20 # EXPR_HOST: \"\"\n\
+         \x20 # default: Default::default()\n\
+         \x20 # EXPR_WORKERS: \"\"\n\
+         \x20 # EXPR_GRACE_PERIOD: \"30\"\n\
+         \x20 # EXPR_IDLE_TIMEOUT: \"90\"\n"
+    );
+    let env = ConfigWithExpressionDefaults::kubernetes_env("expr-secrets");
+    assert!(env.starts_with("env: []\n"));
+    assert!(env.contains(
+        "  # default: std::time::Duration::from_secs(60)\n  # - name: EXPR_TIMEOUT\n  #   value: \"\"\n"
+    ));
+    assert!(env.contains("  # - name: EXPR_IDLE_TIMEOUT\n  #   value: \"90\"\n"));
+}
+
+#[test]
+fn test_markdown_table() {
+    assert_eq!(
+        ConfigWithSpecs::markdown_table(),
+        "| Name | Type | Default | Required | `_FILE` | Description |\n\
+         | ---- | ---- | ------- | -------- | ------- | ----------- |\n\
+         | `SPEC_API_KEY` | `String` |  | yes | yes | API key for the upstream service<br>Mount it as a file in production. |\n\
+         | `SPEC_ALLOWED_HOSTS` | `Option<Vec<String>>` |  | no | no |  |\n\
+         \n\
+         ### Server\n\
+         \n\
+         | Name | Type | Default | Required | `_FILE` | Description |\n\
+         | ---- | ---- | ------- | -------- | ------- | ----------- |\n\
+         | `SPEC_PORT` | `u16` | `8080` | no | no | Port the HTTP server listens on |\n\
+         \n\
+         ### Database\n\
+         \n\
+         | Name | Type | Default | Required | `_FILE` | Description |\n\
+         | ---- | ---- | ------- | -------- | ------- | ----------- |\n\
+         | `SPEC_DB_URL` | `String` |  | yes | no |  |\n"
+    );
+    assert!(ConfigWithSpecs::html_table().contains("<h3>Database</h3>"));
+}
+
+#[test]
+fn test_json_schema() {
+    let schema: serde_json::Value = serde_json::from_str(&ConfigWithSpecs::json_schema()).unwrap();
+    let properties = &schema["properties"];
+
+    assert_eq!(properties["SPEC_PORT"]["pattern"], r"^\+?[0-9]+$");
+    assert_eq!(properties["SPEC_SERVER_PORT"]["deprecated"], true);
+    assert!(properties["SPEC_API_KEY_FILE"].is_object());
+    assert_eq!(
+        properties["SPEC_ALLOWED_HOSTS"],
+        serde_json::json!({ "type": "string" })
+    );
+    assert!(properties.get("SPEC_CLIENT").is_none());
+
+    assert_eq!(schema["required"], serde_json::json!(["SPEC_DB_URL"]));
+    assert_eq!(
+        schema["allOf"][0]["anyOf"],
+        serde_json::json!([
+            { "required": ["SPEC_API_KEY"] },
+            { "required": ["SPEC_API_KEY_FILE"] },
+        ])
+    );
+}
+
+#[test]
+fn test_deployment_snippets() {
+    assert_eq!(
+        ConfigWithSpecs::compose_env(),
+        "environment:\n\
+         \x20 # Port the HTTP server listens on\n\
+         \x20 # SPEC_PORT: \"8080\"\n\
+         \x20 # API key for the upstream service\n\
+         \x20 #\n\
+         \x20 # Mount it as a file in production.\n\
+         \x20 # required\n\
+         \x20 SPEC_API_KEY_FILE: /run/secrets/api-key\n\
+         \x20 # SPEC_ALLOWED_HOSTS: \"\"\n\
+         \x20 # required\n\
+         \x20 SPEC_DB_URL: \"\"\n\
+         secrets:\n\
+         \x20 - api-key\n\
+         # Top-level definitions of the secrets above:\n\
+         # secrets:\n\
+         #   api-key:\n\
+         #     file: ./secrets/api-key\n"
+    );
+
+    let env = ConfigWithSpecs::kubernetes_env("spec-secrets");
+    assert!(env.starts_with("env:\n"));
+    assert!(env.contains("  - name: SPEC_API_KEY_FILE\n    value: /etc/secrets/api-key\n"));
+    assert!(env.contains("  - name: SPEC_DB_URL\n    value: \"\"\n"));
+    assert!(env.contains("  # - name: SPEC_PORT\n  #   value: \"8080\"\n"));
+    assert!(env.contains("#       secretName: spec-secrets\n"));
+}